@@ -84,6 +84,37 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         b.iter(|| FancyDuration(ChronoDuration::seconds(black_box(31 * 24 * 60 * 60 + 61))))
     });
 
+    c.bench_function("fancy duration to_string: std", |b| {
+        b.iter(|| FancyDuration(StdDuration::new(black_box(2 * 24 * 60 * 60 + 61), 0)).to_string())
+    });
+    c.bench_function("fancy duration format_to_array: std", |b| {
+        b.iter(|| {
+            FancyDuration(StdDuration::new(black_box(2 * 24 * 60 * 60 + 61), 0)).format_to_array()
+        })
+    });
+    c.bench_function("fancy duration write_to: std", |b| {
+        let mut s = String::with_capacity(64);
+        b.iter(|| {
+            s.clear();
+            FancyDuration(StdDuration::new(black_box(2 * 24 * 60 * 60 + 61), 0))
+                .write_to(&mut s)
+                .unwrap();
+        })
+    });
+    #[cfg(feature = "time")]
+    c.bench_function("fancy duration format_to_array: time", |b| {
+        b.iter(|| {
+            FancyDuration(TimeDuration::new(black_box(2 * 24 * 60 * 60 + 61), 0)).format_to_array()
+        })
+    });
+    #[cfg(feature = "chrono")]
+    c.bench_function("fancy duration format_to_array: chrono", |b| {
+        b.iter(|| {
+            FancyDuration(ChronoDuration::seconds(black_box(2 * 24 * 60 * 60 + 61)))
+                .format_to_array()
+        })
+    });
+
     let times: [&str; 5] = ["1d2m3s", "1y 2w 3d 5h", "10ns", "3s100ms", "3m 2w 3d 4h 1m"];
 
     c.bench_function("fancy duration parse one: std", |b| {
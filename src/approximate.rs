@@ -0,0 +1,242 @@
+//! Approximate, fuzzy phrasing of durations, such as "about 2 hours".
+
+use crate::{AsTimes, Calendar, DurationBreakdown, DurationPart, FancyDuration};
+use std::time::Duration;
+
+/// What a [Threshold] produces for the durations it covers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Phrase {
+    /// Fixed text, e.g. "less than a minute".
+    Text(String),
+    /// The duration rounded to the nearest `unit` and followed by the unit name, e.g. "about 2
+    /// hours" with a prefix of "about ".
+    Count { prefix: String, unit: DurationPart },
+}
+
+impl Phrase {
+    /// Construct a [Phrase::Text].
+    pub fn text(text: &str) -> Self {
+        Phrase::Text(text.to_string())
+    }
+
+    /// Construct a [Phrase::Count].
+    pub fn count(prefix: &str, unit: DurationPart) -> Self {
+        Phrase::Count {
+            prefix: prefix.to_string(),
+            unit,
+        }
+    }
+}
+
+/// A [Threshold] is an entry in an [ApproximateFormat] table, covering durations below `below`
+/// which are not covered by an earlier entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Threshold {
+    pub below: Duration,
+    pub phrase: Phrase,
+}
+
+impl Threshold {
+    /// Construct a threshold.
+    pub fn new(below: Duration, phrase: Phrase) -> Self {
+        Self { below, phrase }
+    }
+}
+
+/// An [ApproximateFormat] describes durations in a fuzzy manner suitable for notifications, such
+/// as "about 2 hours" for "1h 47m" or "less than a minute" for "40s", rather than exactly like
+/// [FancyDuration::format] does. It is driven by a table of [Threshold]s in ascending order; the
+/// first threshold a duration is below determines the [Phrase] used. Durations beyond the last
+/// threshold use the last phrase.
+///
+/// The default table follows the commonly used thresholds from `moment` and `date-fns`, with
+/// months and years according to the default [Calendar]:
+///
+/// | Duration                 | Output                      |
+/// |--------------------------|-----------------------------|
+/// | below 45s                | less than a minute          |
+/// | below 44m 30s            | 1 minute ... 44 minutes     |
+/// | below 23h 59m 30s        | about 1 hour ... 24 hours   |
+/// | below 42h                | 1 day                       |
+/// | below 30d                | 2 days ... 30 days          |
+/// | below 60d                | about 1 month ... 2 months  |
+/// | below 365d               | 2 months ... 12 months      |
+/// | beyond                   | about 1 year, 2 years, ...  |
+///
+/// ```
+/// use std::time::Duration;
+/// use fancy_duration::{ApproximateFormat, DurationPart, FancyDuration, Phrase, Threshold};
+///
+/// let duration = FancyDuration(Duration::new(107 * 60, 0));
+/// assert_eq!(duration.format_approximate(), "about 2 hours");
+///
+/// let format = ApproximateFormat::new(vec![
+///     Threshold::new(Duration::new(60, 0), Phrase::text("moments")),
+///     Threshold::new(Duration::MAX, Phrase::count("roughly ", DurationPart::Minutes)),
+/// ]);
+/// assert_eq!(duration.format_approximate_with(&format), "roughly 107 minutes");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApproximateFormat {
+    thresholds: Vec<Threshold>,
+}
+
+impl Default for ApproximateFormat {
+    fn default() -> Self {
+        const MINUTE: u64 = 60;
+        const HOUR: u64 = 60 * MINUTE;
+        const DAY: u64 = 24 * HOUR;
+
+        Self::new(vec![
+            Threshold::new(Duration::new(45, 0), Phrase::text("less than a minute")),
+            Threshold::new(
+                Duration::new(44 * MINUTE + 30, 0),
+                Phrase::count("", DurationPart::Minutes),
+            ),
+            Threshold::new(
+                Duration::new(23 * HOUR + 59 * MINUTE + 30, 0),
+                Phrase::count("about ", DurationPart::Hours),
+            ),
+            Threshold::new(Duration::new(42 * HOUR, 0), Phrase::text("1 day")),
+            Threshold::new(
+                Duration::new(30 * DAY, 0),
+                Phrase::count("", DurationPart::Days),
+            ),
+            Threshold::new(
+                Duration::new(60 * DAY, 0),
+                Phrase::count("about ", DurationPart::Months),
+            ),
+            Threshold::new(
+                Duration::new(365 * DAY, 0),
+                Phrase::count("", DurationPart::Months),
+            ),
+            Threshold::new(Duration::MAX, Phrase::count("about ", DurationPart::Years)),
+        ])
+    }
+}
+
+impl ApproximateFormat {
+    /// Construct a format from a table of thresholds, in ascending order.
+    pub fn new(thresholds: Vec<Threshold>) -> Self {
+        Self { thresholds }
+    }
+
+    /// The table of thresholds.
+    pub fn thresholds(&self) -> &[Threshold] {
+        &self.thresholds
+    }
+
+    /// Write the approximate phrasing of the (seconds, nanoseconds) pair to `w`.
+    pub(crate) fn write_to<W: std::fmt::Write>(
+        &self,
+        w: &mut W,
+        times: (u64, u64),
+    ) -> std::fmt::Result {
+        let duration = Duration::new(times.0, 0).saturating_add(Duration::from_nanos(times.1));
+
+        let phrase = match self
+            .thresholds
+            .iter()
+            .find(|threshold| duration < threshold.below)
+            .or_else(|| self.thresholds.last())
+        {
+            Some(threshold) => &threshold.phrase,
+            None => return FancyDuration(duration).write_to(w),
+        };
+
+        match phrase {
+            Phrase::Text(text) => w.write_str(text),
+            Phrase::Count { prefix, unit } => {
+                // rounding to the nearest unit is done by adding half of one before breaking down.
+                let half = unit.as_ns() / 2;
+                let rounded = duration
+                    .checked_add(Duration::new(
+                        (half / 1e9 as u128) as u64,
+                        (half % 1e9 as u128) as u32,
+                    ))
+                    .unwrap_or(duration);
+
+                let count = DurationBreakdown::new_ranged(
+                    rounded.as_secs(),
+                    rounded.subsec_nanos() as u64,
+                    unit,
+                    unit,
+                    &Calendar::default(),
+                )
+                .get(unit);
+
+                write!(w, "{}{} {}", prefix, count, unit.name())?;
+
+                if count != 1 {
+                    w.write_char('s')?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<D> FancyDuration<D>
+where
+    D: AsTimes + Clone,
+{
+    /// Supply an approximate phrasing of the duration, such as "about 2 hours", using the default
+    /// [ApproximateFormat].
+    pub fn format_approximate(&self) -> String {
+        self.format_approximate_with(&ApproximateFormat::default())
+    }
+
+    /// Supply an approximate phrasing of the duration according to the [ApproximateFormat].
+    pub fn format_approximate_with(&self, format: &ApproximateFormat) -> String {
+        let mut s = String::new();
+        format
+            .write_to(&mut s, self.0.as_times())
+            .expect("writing to a String cannot fail");
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FancyDuration;
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_approximate() {
+        const MINUTE: u64 = 60;
+        const HOUR: u64 = 60 * MINUTE;
+        const DAY: u64 = 24 * HOUR;
+
+        let approximate_table = [
+            (0, "less than a minute"),
+            (40, "less than a minute"),
+            (44, "less than a minute"),
+            (45, "1 minute"),
+            (89, "1 minute"),
+            (90, "2 minutes"),
+            (44 * MINUTE, "44 minutes"),
+            (45 * MINUTE, "about 1 hour"),
+            (HOUR + 47 * MINUTE, "about 2 hours"),
+            (23 * HOUR, "about 23 hours"),
+            (23 * HOUR + 59 * MINUTE + 30, "1 day"),
+            (41 * HOUR, "1 day"),
+            (42 * HOUR, "2 days"),
+            (29 * DAY, "29 days"),
+            (30 * DAY, "about 1 month"),
+            (50 * DAY, "about 2 months"),
+            (100 * DAY, "3 months"),
+            (400 * DAY, "about 1 year"),
+            (3 * 365 * DAY, "about 3 years"),
+        ];
+
+        for (seconds, formatted) in approximate_table {
+            assert_eq!(
+                FancyDuration(Duration::new(seconds, 0)).format_approximate(),
+                formatted,
+                "{} seconds",
+                seconds
+            );
+        }
+    }
+}
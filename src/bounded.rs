@@ -0,0 +1,218 @@
+//! Durations which are validated to be within a range, such as configured timeouts.
+
+use crate::{AsTimes, DurationFormat, FancyDuration};
+use std::time::Duration;
+
+/// A [BoundedFancyDuration] is a [FancyDuration] which is at least `MIN_MS` and at most `MAX_MS`
+/// milliseconds long. Out of range durations are rejected when constructed, parsed, or
+/// deserialized with serde, with an error which shows the limits in fancy format. Negative
+/// durations are always out of range, and are rejected when parsed as any other duration which
+/// cannot be negative is, see [AsTimes::try_from_signed_times].
+///
+/// ```
+/// use std::time::Duration;
+/// use fancy_duration::BoundedFancyDuration;
+///
+/// // between 100ms and 5m.
+/// type RequestTimeout = BoundedFancyDuration<Duration, 100, 300_000>;
+///
+/// assert_eq!(RequestTimeout::parse("30s").unwrap().duration(), Duration::new(30, 0));
+/// assert_eq!(
+///     RequestTimeout::parse("10m").unwrap_err().to_string(),
+///     "10m is out of range, which is 100ms to 5m"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundedFancyDuration<D: AsTimes + Clone, const MIN_MS: u64, const MAX_MS: u64>(
+    FancyDuration<D>,
+);
+
+impl<D, const MIN_MS: u64, const MAX_MS: u64> BoundedFancyDuration<D, MIN_MS, MAX_MS>
+where
+    D: AsTimes + Clone,
+{
+    /// The shortest duration allowed.
+    pub const MIN: Duration = Duration::from_millis(MIN_MS);
+    /// The longest duration allowed.
+    pub const MAX: Duration = Duration::from_millis(MAX_MS);
+
+    /// Construct a bounded duration, returning an error if `d` is out of range.
+    pub fn new(d: D) -> Result<Self, anyhow::Error> {
+        let (s, ns) = d.as_times();
+        let length = s as u128 * 1e9 as u128 + ns as u128;
+
+        if d.is_negative() || length < Self::MIN.as_nanos() || length > Self::MAX.as_nanos() {
+            return Err(anyhow::anyhow!(
+                "{} is out of range, which is {} to {}",
+                FancyDuration::new(d),
+                FancyDuration::new(Self::MIN),
+                FancyDuration::new(Self::MAX)
+            ));
+        }
+
+        Ok(Self(FancyDuration::new(d)))
+    }
+
+    /// Parse a string that contains a human-readable duration, returning an error if it is out
+    /// of range. See [FancyDuration::parse].
+    pub fn parse(s: &str) -> Result<Self, anyhow::Error> {
+        Self::new(FancyDuration::<D>::parse(s)?.0)
+    }
+
+    /// Parse a string that contains a human-readable duration according to `format`, returning
+    /// an error if it is out of range. See [FancyDuration::parse_with].
+    pub fn parse_with(s: &str, format: &DurationFormat) -> Result<Self, anyhow::Error> {
+        Self::new(FancyDuration::<D>::parse_with(s, format)?.0)
+    }
+
+    /// Retrieve the inner duration.
+    pub fn duration(&self) -> D {
+        self.0.duration()
+    }
+
+    /// Retrieve the [FancyDuration], for formatting.
+    pub fn fancy_duration(&self) -> &FancyDuration<D> {
+        &self.0
+    }
+}
+
+impl<D, const MIN_MS: u64, const MAX_MS: u64> std::str::FromStr
+    for BoundedFancyDuration<D, MIN_MS, MAX_MS>
+where
+    D: AsTimes + Clone,
+{
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl<D, const MIN_MS: u64, const MAX_MS: u64> std::fmt::Display
+    for BoundedFancyDuration<D, MIN_MS, MAX_MS>
+where
+    D: AsTimes + Clone,
+{
+    /// Formats as [FancyDuration] does.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<D, const MIN_MS: u64, const MAX_MS: u64> ::serde::Serialize
+    for BoundedFancyDuration<D, MIN_MS, MAX_MS>
+where
+    D: AsTimes + Clone,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+/// Deserializes as [FancyDuration] does, rejecting out of range durations.
+#[cfg(feature = "serde")]
+impl<'de, D, const MIN_MS: u64, const MAX_MS: u64> ::serde::Deserialize<'de>
+    for BoundedFancyDuration<D, MIN_MS, MAX_MS>
+where
+    D: AsTimes + Clone,
+{
+    fn deserialize<De>(deserializer: De) -> Result<Self, De::Error>
+    where
+        De: ::serde::Deserializer<'de>,
+    {
+        Self::new(FancyDuration::<D>::deserialize(deserializer)?.0)
+            .map_err(::serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoundedFancyDuration;
+    use std::time::Duration;
+
+    type Timeout = BoundedFancyDuration<Duration, 100, 300_000>;
+
+    #[test]
+    fn test_bounded() {
+        let bounded_table = [
+            ("100ms", Some(Duration::new(0, 100000000))),
+            ("30s", Some(Duration::new(30, 0))),
+            ("5m", Some(Duration::new(300, 0))),
+            ("99ms 999us", None),
+            ("5m 1ns", None),
+            ("0", None),
+            ("-30s", None),
+            ("-30", None),
+        ];
+
+        for (s, duration) in bounded_table {
+            assert_eq!(
+                Timeout::parse(s).ok().map(|bounded| bounded.duration()),
+                duration,
+                "{}",
+                s
+            );
+            assert_eq!(
+                s.parse::<Timeout>().ok().map(|bounded| bounded.duration()),
+                duration,
+                "{}",
+                s
+            );
+        }
+
+        assert_eq!(Timeout::new(Duration::new(1, 0)).unwrap().to_string(), "1s");
+        assert_eq!(
+            Timeout::new(Duration::new(0, 1)).unwrap_err().to_string(),
+            "1ns is out of range, which is 100ms to 5m"
+        );
+        assert_eq!(Timeout::MIN, Duration::new(0, 100000000));
+        assert_eq!(Timeout::MAX, Duration::new(300, 0));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_bounded_serde() {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize)]
+        struct Config {
+            request_timeout: Timeout,
+        }
+
+        let json = r#"{"request_timeout":"1m 30s"}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.request_timeout.duration(), Duration::new(90, 0));
+        assert_eq!(serde_json::to_string(&config).unwrap(), json);
+
+        let err = serde_json::from_str::<Config>(r#"{"request_timeout":"1h"}"#)
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string()
+                .starts_with("1h is out of range, which is 100ms to 5m"),
+            "{}",
+            err
+        );
+
+        for json in [
+            r#"{"request_timeout":"-30s"}"#,
+            r#"{"request_timeout":"-30"}"#,
+            r#"{"request_timeout":-30}"#,
+        ] {
+            assert!(serde_json::from_str::<Config>(json).is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "jiff")]
+    fn test_bounded_negative() {
+        let err = BoundedFancyDuration::<jiff::SignedDuration, 0, 1000>::parse("-1s")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(err, "-1s is out of range, which is 0 to 1s");
+    }
+}
@@ -0,0 +1,998 @@
+//! Calendar-aware durations, which keep years, months and days as they were written so they can
+//! be applied to dates and times correctly.
+
+use crate::{out_of_range, parse_parts, Calendar, DurationBreakdown, DurationPart};
+use std::time::Duration;
+
+/// A [CalendarDuration] is a duration whose years, months and days are kept as-is, rather than
+/// being flattened into seconds like [crate::FancyDuration] does. Adding "1m" to January 31st
+/// cannot be done correctly once a month has become 30 days worth of seconds; a
+/// [CalendarDuration] can be applied to a date or time with [CalendarDuration::add_to] and
+/// [CalendarDuration::sub_from] to do this properly.
+///
+/// Weeks are kept as seven days, and anything smaller than a day (hours and below) is kept as an
+/// exact amount of time in `time`. As with [crate::FancyDuration], "m" is only months when a
+/// larger unit or minutes follow it, so a lone month is written as "1m 0d".
+///
+/// Application works in the following order, the same as most date libraries:
+///
+/// - Years and months are applied together to the year and month of the date. If the day does not
+///   exist in the resulting month, it is clamped to the end of the month, so January 31st plus one
+///   month is February 28th (or 29th), and February 29th plus one year is February 28th.
+/// - Days are applied to the resulting date.
+/// - The time is applied last, as an exact amount of elapsed time.
+///
+/// For types with a time zone, years, months and days are applied to the local ("wall clock")
+/// date and time, so adding "1d" across a daylight saving transition keeps the same local time,
+/// while adding "24h" adds exactly 24 hours. If the resulting local time is ambiguous, the earlier
+/// of the two is chosen. If it does not exist because it falls in a gap, the offset before the
+/// transition is used, moving the time forward by the length of the gap.
+///
+/// ```
+/// use fancy_duration::CalendarDuration;
+///
+/// let duration = CalendarDuration::parse("1y 2m 3d 4h").unwrap();
+/// assert_eq!(duration.years, 1);
+/// assert_eq!(duration.months, 2);
+/// assert_eq!(duration.days, 3);
+/// assert_eq!(duration.time, std::time::Duration::new(4 * 60 * 60, 0));
+/// assert_eq!(duration.to_string(), "1y 2m 3d 4h");
+///
+/// #[cfg(feature = "chrono")]
+/// {
+///     let date = chrono::NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+///     let duration = CalendarDuration::parse("1m 0d").unwrap();
+///     assert_eq!(duration.add_to(date), chrono::NaiveDate::from_ymd_opt(2024, 2, 29));
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CalendarDuration {
+    pub years: u64,
+    pub months: u64,
+    pub days: u64,
+    pub time: Duration,
+}
+
+impl CalendarDuration {
+    /// Construct a calendar duration from its parts.
+    pub fn new(years: u64, months: u64, days: u64, time: Duration) -> Self {
+        Self {
+            years,
+            months,
+            days,
+            time,
+        }
+    }
+
+    /// Parse a string that contains a human-readable duration, keeping the calendar units. See
+    /// [crate::FancyDuration] for more information on how times are represented.
+    pub fn parse(s: &str) -> Result<Self, anyhow::Error> {
+        let mut obj = Self::default();
+        let mut ns: u128 = 0;
+
+        for (part, value) in parse_parts(s)? {
+            let (field, value) = match part {
+                DurationPart::Years => (&mut obj.years, Some(value)),
+                DurationPart::Months => (&mut obj.months, Some(value)),
+                DurationPart::Weeks => (&mut obj.days, value.checked_mul(7)),
+                DurationPart::Days => (&mut obj.days, Some(value)),
+                _ => {
+                    ns = ns
+                        .checked_add(value as u128 * part.as_ns())
+                        .ok_or_else(out_of_range)?;
+                    continue;
+                }
+            };
+
+            *field = value
+                .and_then(|value| field.checked_add(value))
+                .ok_or_else(out_of_range)?;
+        }
+
+        obj.time = Duration::new((ns / 1e9 as u128).try_into()?, (ns % 1e9 as u128) as u32);
+
+        Ok(obj)
+    }
+
+    /// Flatten into a (seconds, nanoseconds) pair, as [crate::AsTimes] does, with years, months
+    /// and days according to the calendar. This saturates at [u64::MAX] seconds.
+    pub fn as_times(&self, calendar: &Calendar) -> (u64, u64) {
+        DurationBreakdown {
+            years: self.years,
+            months: self.months,
+            days: self.days,
+            seconds: self.time.as_secs(),
+            nanoseconds: self.time.subsec_nanos() as u64,
+            ..Default::default()
+        }
+        .as_times_with(calendar)
+    }
+
+    /// Add this duration to `t`, returning [None] if the result is out of range. See
+    /// [CalendarDuration] for how this is applied.
+    pub fn add_to<T: CalendarArithmetic>(&self, t: T) -> Option<T> {
+        t.add_calendar_duration(self)
+    }
+
+    /// Subtract this duration from `t`, returning [None] if the result is out of range. Months
+    /// are clamped the same way as when adding, so March 31st minus one month is February 28th
+    /// (or 29th).
+    pub fn sub_from<T: CalendarArithmetic>(&self, t: T) -> Option<T> {
+        t.sub_calendar_duration(self)
+    }
+
+    /// The calendar difference between two dates or times, such that adding the result to the
+    /// earlier of the two yields the later one. Years and months are counted first, then days,
+    /// then the remaining exact time, so January 15th to March 20th is "2m 5d". As adding a month
+    /// clamps to the end of the month, January 31st to February 29th is one month, as is January
+    /// 29th to February 29th. The order of the arguments does not matter; the difference is always
+    /// positive. [None] is returned if the difference cannot be represented.
+    ///
+    /// ```
+    /// #[cfg(feature = "chrono")]
+    /// {
+    ///     use chrono::{TimeZone, Utc};
+    ///     use fancy_duration::CalendarDuration;
+    ///
+    ///     let start = Utc.with_ymd_and_hms(2023, 1, 15, 8, 0, 0).unwrap();
+    ///     let end = Utc.with_ymd_and_hms(2024, 3, 18, 9, 30, 0).unwrap();
+    ///     let duration = CalendarDuration::between(&start, &end).unwrap();
+    ///     assert_eq!(duration.to_string(), "1y 2m 3d 1h 30m");
+    /// }
+    /// ```
+    pub fn between<T: CalendarDifference>(start: &T, end: &T) -> Option<Self> {
+        let (start, end) = if start <= end {
+            (start, end)
+        } else {
+            (end, start)
+        };
+
+        let fits = |months: u64, days: u64| {
+            start
+                .add_calendar_duration(&Self::new(0, months, days, Duration::ZERO))
+                .is_some_and(|t| t <= *end)
+        };
+
+        let (start_year, start_month) = start.year_month();
+        let (end_year, end_month) = end.year_month();
+
+        // estimates are corrected in both directions, as clamping and differing offsets can put
+        // them off by one.
+        let mut months = ((end_year as i64 - start_year as i64) * 12 + end_month as i64
+            - start_month as i64)
+            .max(0) as u64;
+
+        while months > 0 && !fits(months, 0) {
+            months -= 1;
+        }
+
+        while fits(months + 1, 0) {
+            months += 1;
+        }
+
+        let mut days = start
+            .add_calendar_duration(&Self::new(0, months, 0, Duration::ZERO))?
+            .days_until(end)
+            .max(0) as u64;
+
+        while days > 0 && !fits(months, days) {
+            days -= 1;
+        }
+
+        while fits(months, days + 1) {
+            days += 1;
+        }
+
+        let time = start
+            .add_calendar_duration(&Self::new(0, months, days, Duration::ZERO))?
+            .elapsed_until(end)?;
+
+        Some(Self::new(months / 12, months % 12, days, time))
+    }
+
+    /// The total amount of months, with the sign applied.
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    fn signed_months(&self, sign: i64) -> Option<i64> {
+        i64::try_from(self.years.checked_mul(12)?.checked_add(self.months)?)
+            .ok()?
+            .checked_mul(sign)
+    }
+
+    /// The amount of days, with the sign applied. `whole_days_of_time` also includes the days in
+    /// `time`, for types which cannot represent time below a day.
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    fn signed_days(&self, sign: i64, whole_days_of_time: bool) -> Option<i64> {
+        let mut days = self.days;
+
+        if whole_days_of_time {
+            days = days.checked_add(self.time.as_secs() / (24 * 60 * 60))?;
+        }
+
+        i64::try_from(days).ok()?.checked_mul(sign)
+    }
+}
+
+impl std::str::FromStr for CalendarDuration {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl std::fmt::Display for CalendarDuration {
+    /// Formats the standard representation, or the compact one with `{:#}`. Days are represented
+    /// as weeks and days, and time is represented in hours and below, so "1d 36h" remains as
+    /// such instead of becoming "2d 12h". Months which are not followed by weeks through minutes
+    /// are followed by "0d", e.g. "1m 0d", so that they are not parsed back as minutes.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pad = !f.alternate();
+        let mut breakdown = DurationBreakdown::new_ranged(
+            self.time.as_secs(),
+            self.time.subsec_nanos() as u64,
+            &DurationPart::Hours,
+            &DurationPart::Nanoseconds,
+            &Calendar::default(),
+        );
+
+        breakdown.weeks = self.days / 7;
+        breakdown.days = self.days % 7;
+
+        if self.months == 0
+            || breakdown.weeks > 0
+            || breakdown.days > 0
+            || breakdown.hours > 0
+            || breakdown.minutes > 0
+        {
+            breakdown.years = self.years;
+            breakdown.months = self.months;
+            return breakdown.write_to(f, pad);
+        }
+
+        let calendar = DurationBreakdown {
+            years: self.years,
+            months: self.months,
+            ..Default::default()
+        };
+
+        calendar.write_to(f, pad)?;
+        f.write_str(if pad { " 0d" } else { "0d" })?;
+
+        if breakdown != DurationBreakdown::default() {
+            if pad {
+                f.write_str(" ")?;
+            }
+
+            breakdown.write_to(f, pad)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// [CalendarArithmetic] is implemented for date and time types a [CalendarDuration] can be
+/// applied to. Implementations are supplied for the `chrono` and `time` crates when their
+/// features are enabled.
+pub trait CalendarArithmetic: Sized {
+    /// Add the duration, returning [None] if the result is out of range.
+    fn add_calendar_duration(&self, duration: &CalendarDuration) -> Option<Self>;
+    /// Subtract the duration, returning [None] if the result is out of range.
+    fn sub_calendar_duration(&self, duration: &CalendarDuration) -> Option<Self>;
+}
+
+/// [CalendarDifference] is implemented for date and time types which can be compared with
+/// [CalendarDuration::between]. Implementations are supplied for the `chrono` and `time` crates
+/// when their features are enabled.
+pub trait CalendarDifference: CalendarArithmetic + PartialOrd {
+    /// The year and 1-based month of the local date. This is only used as an estimate.
+    fn year_month(&self) -> (i32, u32);
+    /// The amount of days from the local date of `self` to that of `other`. This is only used as
+    /// an estimate.
+    fn days_until(&self, other: &Self) -> i64;
+    /// The exact time elapsed from `self` to `other`, which is not before `self`.
+    fn elapsed_until(&self, other: &Self) -> Option<Duration>;
+}
+
+/// Move the (year, month, day) by `months`, clamping the day to the end of the resulting month.
+/// Months are 1-based.
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub(crate) fn shift_months(
+    year: i32,
+    month: u32,
+    day: u32,
+    months: i64,
+) -> Option<(i32, u32, u32)> {
+    let index = (year as i64 * 12 + month as i64 - 1).checked_add(months)?;
+    let year = i32::try_from(index.div_euclid(12)).ok()?;
+    let month = index.rem_euclid(12) as u32 + 1;
+
+    Some((year, month, day.min(days_in_month(year, month))))
+}
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub(crate) fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub(crate) fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+#[cfg(feature = "chrono")]
+pub(crate) mod chrono_impl {
+    use super::{shift_months, CalendarArithmetic, CalendarDifference, CalendarDuration};
+    use chrono::{
+        DateTime, Datelike, LocalResult, NaiveDate, NaiveDateTime, Offset, TimeDelta, TimeZone,
+    };
+    use std::time::Duration;
+
+    fn shift_date(date: NaiveDate, months: i64, days: i64) -> Option<NaiveDate> {
+        let (year, month, day) = shift_months(date.year(), date.month(), date.day(), months)?;
+        NaiveDate::from_ymd_opt(year, month, day)?.checked_add_signed(TimeDelta::try_days(days)?)
+    }
+
+    fn signed_time(duration: &CalendarDuration, sign: i64) -> Option<TimeDelta> {
+        let time = TimeDelta::from_std(duration.time).ok()?;
+        Some(if sign < 0 { -time } else { time })
+    }
+
+    fn shift_naive_date(
+        date: &NaiveDate,
+        duration: &CalendarDuration,
+        sign: i64,
+    ) -> Option<NaiveDate> {
+        shift_date(
+            *date,
+            duration.signed_months(sign)?,
+            duration.signed_days(sign, true)?,
+        )
+    }
+
+    fn shift_naive_datetime(
+        dt: &NaiveDateTime,
+        duration: &CalendarDuration,
+        sign: i64,
+    ) -> Option<NaiveDateTime> {
+        shift_date(
+            dt.date(),
+            duration.signed_months(sign)?,
+            duration.signed_days(sign, false)?,
+        )?
+        .and_time(dt.time())
+        .checked_add_signed(signed_time(duration, sign)?)
+    }
+
+    fn shift_datetime<Tz: TimeZone>(
+        dt: &DateTime<Tz>,
+        duration: &CalendarDuration,
+        sign: i64,
+    ) -> Option<DateTime<Tz>> {
+        let naive = dt.naive_local();
+        let local = shift_date(
+            naive.date(),
+            duration.signed_months(sign)?,
+            duration.signed_days(sign, false)?,
+        )?
+        .and_time(naive.time());
+
+        resolve_local(dt, &local)?.checked_add_signed(signed_time(duration, sign)?)
+    }
+
+    /// Resolve the local time in the time zone of `dt`. Ambiguous times resolve to the earlier
+    /// time, and times in a gap use the offset in effect before the gap, moving them forward by
+    /// the length of the gap.
+    pub(crate) fn resolve_local<Tz: TimeZone>(
+        dt: &DateTime<Tz>,
+        local: &NaiveDateTime,
+    ) -> Option<DateTime<Tz>> {
+        let tz = dt.timezone();
+
+        match tz.from_local_datetime(local) {
+            LocalResult::Single(dt) => Some(dt),
+            LocalResult::Ambiguous(earliest, _) => Some(earliest),
+            LocalResult::None => {
+                // gaps are rarely more than an hour, so the hour before is usually enough.
+                let offset = (1..=24).find_map(|hours| {
+                    let before = local.checked_sub_signed(TimeDelta::try_hours(hours)?)?;
+                    tz.from_local_datetime(&before)
+                        .earliest()
+                        .map(|before| before.offset().fix().local_minus_utc())
+                })?;
+
+                Some(tz.from_utc_datetime(
+                    &local.checked_sub_signed(TimeDelta::try_seconds(offset.into())?)?,
+                ))
+            }
+        }
+    }
+
+    impl CalendarDifference for NaiveDate {
+        fn year_month(&self) -> (i32, u32) {
+            (self.year(), self.month())
+        }
+
+        fn days_until(&self, other: &Self) -> i64 {
+            other.signed_duration_since(*self).num_days()
+        }
+
+        fn elapsed_until(&self, _other: &Self) -> Option<Duration> {
+            Some(Duration::ZERO)
+        }
+    }
+
+    impl CalendarDifference for NaiveDateTime {
+        fn year_month(&self) -> (i32, u32) {
+            (self.year(), self.month())
+        }
+
+        fn days_until(&self, other: &Self) -> i64 {
+            other.date().signed_duration_since(self.date()).num_days()
+        }
+
+        fn elapsed_until(&self, other: &Self) -> Option<Duration> {
+            other.signed_duration_since(*self).to_std().ok()
+        }
+    }
+
+    impl<Tz: TimeZone> CalendarDifference for DateTime<Tz> {
+        fn year_month(&self) -> (i32, u32) {
+            (self.year(), self.month())
+        }
+
+        fn days_until(&self, other: &Self) -> i64 {
+            other
+                .naive_local()
+                .date()
+                .signed_duration_since(self.naive_local().date())
+                .num_days()
+        }
+
+        fn elapsed_until(&self, other: &Self) -> Option<Duration> {
+            other.clone().signed_duration_since(self).to_std().ok()
+        }
+    }
+
+    /// Time below a day is dropped, as a date cannot represent it.
+    impl CalendarArithmetic for NaiveDate {
+        fn add_calendar_duration(&self, duration: &CalendarDuration) -> Option<Self> {
+            shift_naive_date(self, duration, 1)
+        }
+
+        fn sub_calendar_duration(&self, duration: &CalendarDuration) -> Option<Self> {
+            shift_naive_date(self, duration, -1)
+        }
+    }
+
+    impl CalendarArithmetic for NaiveDateTime {
+        fn add_calendar_duration(&self, duration: &CalendarDuration) -> Option<Self> {
+            shift_naive_datetime(self, duration, 1)
+        }
+
+        fn sub_calendar_duration(&self, duration: &CalendarDuration) -> Option<Self> {
+            shift_naive_datetime(self, duration, -1)
+        }
+    }
+
+    impl<Tz: TimeZone> CalendarArithmetic for DateTime<Tz> {
+        fn add_calendar_duration(&self, duration: &CalendarDuration) -> Option<Self> {
+            shift_datetime(self, duration, 1)
+        }
+
+        fn sub_calendar_duration(&self, duration: &CalendarDuration) -> Option<Self> {
+            shift_datetime(self, duration, -1)
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_impl {
+    use super::{shift_months, CalendarArithmetic, CalendarDifference, CalendarDuration};
+    use std::time::Duration;
+    use time::{Date, Month, OffsetDateTime, PrimitiveDateTime};
+
+    fn shift_date(date: Date, months: i64, days: i64) -> Option<Date> {
+        let (year, month, day) =
+            shift_months(date.year(), date.month() as u32, date.day() as u32, months)?;
+
+        Date::from_calendar_date(year, Month::try_from(month as u8).ok()?, day as u8)
+            .ok()?
+            .checked_add(time::Duration::seconds(days.checked_mul(24 * 60 * 60)?))
+    }
+
+    fn signed_time(duration: &CalendarDuration, sign: i64) -> Option<time::Duration> {
+        let time = time::Duration::try_from(duration.time).ok()?;
+        Some(if sign < 0 { -time } else { time })
+    }
+
+    fn shift_calendar_date(
+        date: Date,
+        duration: &CalendarDuration,
+        sign: i64,
+        whole_days_of_time: bool,
+    ) -> Option<Date> {
+        shift_date(
+            date,
+            duration.signed_months(sign)?,
+            duration.signed_days(sign, whole_days_of_time)?,
+        )
+    }
+
+    impl CalendarDifference for Date {
+        fn year_month(&self) -> (i32, u32) {
+            (self.year(), self.month() as u32)
+        }
+
+        fn days_until(&self, other: &Self) -> i64 {
+            (*other - *self).whole_days()
+        }
+
+        fn elapsed_until(&self, _other: &Self) -> Option<Duration> {
+            Some(Duration::ZERO)
+        }
+    }
+
+    impl CalendarDifference for PrimitiveDateTime {
+        fn year_month(&self) -> (i32, u32) {
+            (self.year(), self.month() as u32)
+        }
+
+        fn days_until(&self, other: &Self) -> i64 {
+            (other.date() - self.date()).whole_days()
+        }
+
+        fn elapsed_until(&self, other: &Self) -> Option<Duration> {
+            (*other - *self).try_into().ok()
+        }
+    }
+
+    impl CalendarDifference for OffsetDateTime {
+        fn year_month(&self) -> (i32, u32) {
+            (self.year(), self.month() as u32)
+        }
+
+        fn days_until(&self, other: &Self) -> i64 {
+            (other.to_offset(self.offset()).date() - self.date()).whole_days()
+        }
+
+        fn elapsed_until(&self, other: &Self) -> Option<Duration> {
+            (*other - *self).try_into().ok()
+        }
+    }
+
+    /// Time below a day is dropped, as a date cannot represent it.
+    impl CalendarArithmetic for Date {
+        fn add_calendar_duration(&self, duration: &CalendarDuration) -> Option<Self> {
+            shift_calendar_date(*self, duration, 1, true)
+        }
+
+        fn sub_calendar_duration(&self, duration: &CalendarDuration) -> Option<Self> {
+            shift_calendar_date(*self, duration, -1, true)
+        }
+    }
+
+    impl CalendarArithmetic for PrimitiveDateTime {
+        fn add_calendar_duration(&self, duration: &CalendarDuration) -> Option<Self> {
+            PrimitiveDateTime::new(
+                shift_calendar_date(self.date(), duration, 1, false)?,
+                self.time(),
+            )
+            .checked_add(signed_time(duration, 1)?)
+        }
+
+        fn sub_calendar_duration(&self, duration: &CalendarDuration) -> Option<Self> {
+            PrimitiveDateTime::new(
+                shift_calendar_date(self.date(), duration, -1, false)?,
+                self.time(),
+            )
+            .checked_add(signed_time(duration, -1)?)
+        }
+    }
+
+    /// An [OffsetDateTime] has a fixed offset, so there is no daylight saving time to account
+    /// for; the offset is kept as-is.
+    impl CalendarArithmetic for OffsetDateTime {
+        fn add_calendar_duration(&self, duration: &CalendarDuration) -> Option<Self> {
+            self.replace_date(shift_calendar_date(self.date(), duration, 1, false)?)
+                .checked_add(signed_time(duration, 1)?)
+        }
+
+        fn sub_calendar_duration(&self, duration: &CalendarDuration) -> Option<Self> {
+            self.replace_date(shift_calendar_date(self.date(), duration, -1, false)?)
+                .checked_add(signed_time(duration, -1)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CalendarDuration;
+    use crate::Calendar;
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_calendar_duration() {
+        let duration_table = [
+            (
+                "1y 2m 3d",
+                CalendarDuration::new(1, 2, 3, Duration::ZERO),
+                "1y 2m 3d",
+            ),
+            (
+                "2w 1d",
+                CalendarDuration::new(0, 0, 15, Duration::ZERO),
+                "2w 1d",
+            ),
+            (
+                "1m 10m 5s 3ms",
+                CalendarDuration::new(0, 1, 0, Duration::new(605, 3000000)),
+                "1m 10m 5s 3ms",
+            ),
+            (
+                "1d 36h",
+                CalendarDuration::new(0, 0, 1, Duration::new(36 * 60 * 60, 0)),
+                "1d 36h",
+            ),
+            ("0", CalendarDuration::default(), "0"),
+        ];
+
+        for (s, duration, formatted) in duration_table {
+            let parsed: CalendarDuration = s.parse().unwrap();
+            assert_eq!(parsed, duration);
+            assert_eq!(parsed.to_string(), formatted);
+        }
+
+        // months are formatted so that they are parsed back as months rather than minutes.
+        let round_trip_table = [
+            (
+                CalendarDuration::new(0, 1, 0, Duration::ZERO),
+                "1m 0d",
+                "1m0d",
+            ),
+            (
+                CalendarDuration::new(1, 2, 0, Duration::ZERO),
+                "1y 2m 0d",
+                "1y2m0d",
+            ),
+            (
+                CalendarDuration::new(0, 1, 0, Duration::new(5, 3000000)),
+                "1m 0d 5s 3ms",
+                "1m0d5s3ms",
+            ),
+            (
+                CalendarDuration::new(0, 1, 0, Duration::new(60, 0)),
+                "1m 1m",
+                "1m1m",
+            ),
+            (CalendarDuration::new(2, 0, 0, Duration::ZERO), "2y", "2y"),
+        ];
+
+        for (duration, formatted, compact) in round_trip_table {
+            assert_eq!(duration.to_string(), formatted);
+            assert_eq!(format!("{:#}", duration), compact);
+            assert_eq!(
+                CalendarDuration::parse(&duration.to_string()).unwrap(),
+                duration
+            );
+            assert_eq!(
+                CalendarDuration::parse(&format!("{:#}", duration)).unwrap(),
+                duration
+            );
+        }
+
+        for s in [
+            "18446744073709551615w",
+            "18446744073709551615y 1y",
+            "18446744073709551615m 1m 0d",
+            "18446744073709551615d 1w",
+            "18446744073709551615h",
+        ] {
+            assert!(CalendarDuration::parse(s).is_err(), "{}", s);
+        }
+
+        assert_eq!(
+            format!("{:#}", CalendarDuration::parse("1y 2m 3h").unwrap()),
+            "1y2m3h"
+        );
+
+        let duration = CalendarDuration::parse("1y 1m 2d 3h").unwrap();
+        assert_eq!(
+            duration.as_times(&Calendar::default()),
+            ((360 + 30 + 2) * 24 * 3600 + 3 * 3600, 0)
+        );
+        let year = 52 * 5 * 8 * 3600;
+        assert_eq!(
+            duration.as_times(&Calendar::business(8, 5)),
+            (year + year / 12 + (2 * 8 + 3) * 3600, 0)
+        );
+        assert_eq!(
+            CalendarDuration::new(u64::MAX, 0, 0, Duration::ZERO).as_times(&Calendar::default()),
+            (u64::MAX, 999999999)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_chrono_daylight_saving() {
+        use chrono::{
+            Datelike, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc,
+        };
+
+        /// Central European time in 2023 and 2024: UTC+1, and UTC+2 from the last Sunday of
+        /// March to the last Sunday of October, changing at 01:00 UTC.
+        #[derive(Clone, Copy, Debug)]
+        struct Cet;
+
+        impl Cet {
+            fn offset_at(utc: &NaiveDateTime) -> FixedOffset {
+                let change = |m, d| {
+                    NaiveDate::from_ymd_opt(utc.year(), m, d)
+                        .unwrap()
+                        .and_hms_opt(1, 0, 0)
+                        .unwrap()
+                };
+                let summer = match utc.year() {
+                    2023 => change(3, 26)..change(10, 29),
+                    _ => change(3, 31)..change(10, 27),
+                };
+
+                let hours = if summer.contains(utc) { 2 } else { 1 };
+                FixedOffset::east_opt(hours * 60 * 60).unwrap()
+            }
+        }
+
+        impl TimeZone for Cet {
+            type Offset = FixedOffset;
+
+            fn from_offset(_: &FixedOffset) -> Self {
+                Cet
+            }
+
+            fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+                self.offset_from_local_datetime(&local.and_hms_opt(12, 0, 0).unwrap())
+            }
+
+            fn offset_from_local_datetime(
+                &self,
+                local: &NaiveDateTime,
+            ) -> LocalResult<FixedOffset> {
+                let mut offsets = [2, 1].into_iter().filter_map(|hours| {
+                    let offset = FixedOffset::east_opt(hours * 60 * 60).unwrap();
+                    let utc = *local - offset;
+                    (Self::offset_at(&utc) == offset).then_some(offset)
+                });
+
+                match (offsets.next(), offsets.next()) {
+                    (Some(earliest), Some(latest)) => LocalResult::Ambiguous(earliest, latest),
+                    (Some(offset), None) => LocalResult::Single(offset),
+                    _ => LocalResult::None,
+                }
+            }
+
+            fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+                Self::offset_at(&utc.and_hms_opt(12, 0, 0).unwrap())
+            }
+
+            fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+                Self::offset_at(utc)
+            }
+        }
+
+        // a summer time plus seven months lands in the spring forward gap, from 02:00 to 03:00,
+        // and moves forward by the length of the gap.
+        let summer = Cet.with_ymd_and_hms(2023, 8, 31, 2, 30, 0).unwrap();
+        let spring = CalendarDuration::parse("7m 0d")
+            .unwrap()
+            .add_to(summer)
+            .unwrap();
+        assert_eq!(spring.naive_local().hour(), 3);
+        assert_eq!(spring.naive_local().minute(), 30);
+        assert_eq!(
+            spring.with_timezone(&Utc),
+            Utc.with_ymd_and_hms(2024, 3, 31, 1, 30, 0).unwrap()
+        );
+
+        // a winter time plus a day does the same.
+        let winter = Cet.with_ymd_and_hms(2024, 3, 30, 2, 30, 0).unwrap();
+        let spring = CalendarDuration::parse("1d")
+            .unwrap()
+            .add_to(winter)
+            .unwrap();
+        assert_eq!(
+            spring.with_timezone(&Utc),
+            Utc.with_ymd_and_hms(2024, 3, 31, 1, 30, 0).unwrap()
+        );
+
+        // ambiguous times in the fall resolve to the earlier time, and hours are then exact, so
+        // an hour later is the repeated 02:30.
+        let summer = Cet.with_ymd_and_hms(2024, 10, 26, 2, 30, 0).unwrap();
+        let fall = CalendarDuration::parse("1d")
+            .unwrap()
+            .add_to(summer)
+            .unwrap();
+        assert_eq!(
+            fall.with_timezone(&Utc),
+            Utc.with_ymd_and_hms(2024, 10, 27, 0, 30, 0).unwrap()
+        );
+        let fall = CalendarDuration::parse("1d 1h")
+            .unwrap()
+            .add_to(summer)
+            .unwrap();
+        assert_eq!(
+            fall.with_timezone(&Utc),
+            Utc.with_ymd_and_hms(2024, 10, 27, 1, 30, 0).unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_chrono_calendar_duration() {
+        use chrono::{NaiveDate, TimeZone};
+
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        let date_table = [
+            ((2024, 1, 31), "1m 0d", (2024, 2, 29), (2023, 12, 31)),
+            ((2023, 1, 31), "1m 0d", (2023, 2, 28), (2022, 12, 31)),
+            ((2024, 2, 29), "1y", (2025, 2, 28), (2023, 2, 28)),
+            ((2024, 3, 31), "1m 1d", (2024, 5, 1), (2024, 2, 28)),
+            ((2024, 12, 15), "1m 2w", (2025, 1, 29), (2024, 11, 1)),
+            ((2024, 1, 1), "1d 36h", (2024, 1, 3), (2023, 12, 30)),
+        ];
+
+        for (start, s, added, subtracted) in date_table {
+            let duration = CalendarDuration::parse(s).unwrap();
+            let start = date(start.0, start.1, start.2);
+
+            assert_eq!(
+                duration.add_to(start),
+                Some(date(added.0, added.1, added.2))
+            );
+            assert_eq!(
+                duration.sub_from(start),
+                Some(date(subtracted.0, subtracted.1, subtracted.2))
+            );
+        }
+
+        let dt = chrono::Utc.with_ymd_and_hms(2024, 1, 31, 22, 0, 0).unwrap();
+        let duration = CalendarDuration::parse("1m 3h").unwrap();
+        assert_eq!(
+            duration.add_to(dt),
+            Some(chrono::Utc.with_ymd_and_hms(2024, 3, 1, 1, 0, 0).unwrap())
+        );
+        assert_eq!(
+            duration.sub_from(dt),
+            Some(
+                chrono::Utc
+                    .with_ymd_and_hms(2023, 12, 31, 19, 0, 0)
+                    .unwrap()
+            )
+        );
+
+        let between_table = [
+            ((2024, 1, 15), (2024, 3, 20), "2m 5d"),
+            ((2024, 1, 31), (2024, 2, 29), "1m 0d"),
+            ((2024, 1, 29), (2024, 2, 28), "4w 2d"),
+            ((2024, 1, 31), (2024, 3, 1), "1m 1d"),
+            ((2023, 2, 28), (2024, 2, 29), "1y 1d"),
+            ((2024, 2, 29), (2025, 2, 28), "1y"),
+            ((2020, 5, 10), (2024, 5, 10), "4y"),
+            ((2024, 5, 10), (2020, 5, 9), "4y 1d"),
+            ((2024, 5, 10), (2024, 5, 10), "0"),
+        ];
+
+        for (start, end, formatted) in between_table {
+            let (start, end) = (date(start.0, start.1, start.2), date(end.0, end.1, end.2));
+            let between = CalendarDuration::between(&start, &end).unwrap();
+            assert_eq!(between.to_string(), formatted);
+            assert_eq!(between.add_to(start.min(end)), Some(start.max(end)));
+        }
+
+        let start = chrono::Utc.with_ymd_and_hms(2023, 1, 31, 22, 0, 0).unwrap();
+        let end = chrono::Utc
+            .with_ymd_and_hms(2024, 3, 1, 21, 30, 15)
+            .unwrap();
+        let between = CalendarDuration::between(&start, &end).unwrap();
+        assert_eq!(between.to_string(), "1y 1m 23h 30m 15s");
+        assert_eq!(between.add_to(start), Some(end));
+
+        let start = chrono::FixedOffset::east_opt(2 * 60 * 60)
+            .unwrap()
+            .with_ymd_and_hms(2024, 1, 1, 1, 0, 0)
+            .unwrap();
+        let end = chrono::Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap();
+        let between = CalendarDuration::between(&start, &end.fixed_offset()).unwrap();
+        assert_eq!(between.to_string(), "1d 1h");
+
+        let naive = date(2024, 1, 31).and_hms_opt(12, 0, 0).unwrap();
+        assert_eq!(
+            duration.add_to(naive),
+            date(2024, 2, 29).and_hms_opt(15, 0, 0)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn test_time_calendar_duration() {
+        use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+        let date = |y, m, d| Date::from_calendar_date(y, Month::try_from(m).unwrap(), d).unwrap();
+        let datetime =
+            |date: Date, h| PrimitiveDateTime::new(date, Time::from_hms(h, 0, 0).unwrap());
+
+        let date_table = [
+            (
+                date(2024, 1, 31),
+                "1m 0d",
+                date(2024, 2, 29),
+                date(2023, 12, 31),
+            ),
+            (
+                date(2024, 2, 29),
+                "1y",
+                date(2025, 2, 28),
+                date(2023, 2, 28),
+            ),
+            (
+                date(2024, 3, 31),
+                "1m 1d",
+                date(2024, 5, 1),
+                date(2024, 2, 28),
+            ),
+            (
+                date(2024, 1, 1),
+                "1d 36h",
+                date(2024, 1, 3),
+                date(2023, 12, 30),
+            ),
+        ];
+
+        for (start, s, added, subtracted) in date_table {
+            let duration = CalendarDuration::parse(s).unwrap();
+            assert_eq!(duration.add_to(start), Some(added));
+            assert_eq!(duration.sub_from(start), Some(subtracted));
+        }
+
+        let duration = CalendarDuration::parse("1m 3h").unwrap();
+        assert_eq!(
+            duration.add_to(datetime(date(2024, 1, 31), 22)),
+            Some(datetime(date(2024, 3, 1), 1))
+        );
+
+        let between_table = [
+            (date(2024, 1, 15), date(2024, 3, 20), "2m 5d"),
+            (date(2024, 1, 31), date(2024, 2, 29), "1m 0d"),
+            (date(2023, 2, 28), date(2024, 2, 29), "1y 1d"),
+            (date(2024, 5, 10), date(2020, 5, 9), "4y 1d"),
+        ];
+
+        for (start, end, formatted) in between_table {
+            let between = CalendarDuration::between(&start, &end).unwrap();
+            assert_eq!(between.to_string(), formatted);
+            assert_eq!(between.add_to(start.min(end)), Some(start.max(end)));
+        }
+
+        let start = datetime(date(2023, 1, 31), 22);
+        let end = datetime(date(2024, 3, 1), 21);
+        let between = CalendarDuration::between(&start, &end).unwrap();
+        assert_eq!(between.to_string(), "1y 1m 23h");
+        assert_eq!(between.add_to(start), Some(end));
+
+        let offset = UtcOffset::from_hms(2, 0, 0).unwrap();
+        let offset_datetime = |date, h| datetime(date, h).assume_offset(offset);
+        let start: OffsetDateTime = offset_datetime(date(2024, 1, 31), 22);
+        assert_eq!(
+            duration.add_to(start),
+            Some(offset_datetime(date(2024, 3, 1), 1))
+        );
+        assert_eq!(
+            duration.sub_from(start),
+            Some(offset_datetime(date(2023, 12, 31), 19))
+        );
+    }
+}
@@ -0,0 +1,304 @@
+//! Support for command line arguments with `clap`, such as `--timeout 1h30m`.
+
+use crate::{check_syntax, AsTimes, FancyDuration, SyntaxError, DURATION_PARTS};
+use ::clap::builder::{StyledStr, TypedValueParser, ValueParserFactory};
+use ::clap::error::{ContextKind, ContextValue, ErrorKind};
+use std::marker::PhantomData;
+
+/// Help for arguments parsed with [FancyDurationValueParser] or [DurationValueParser], with
+/// examples of the accepted syntax, for `Arg::help` or `Arg::long_help`.
+///
+/// ```
+/// use std::time::Duration;
+/// use clap::{Arg, Command};
+/// use fancy_duration::{DurationValueParser, DURATION_HELP};
+///
+/// let help = Command::new("app")
+///     .arg(
+///         Arg::new("timeout")
+///             .long("timeout")
+///             .help(DURATION_HELP)
+///             .value_parser(DurationValueParser::<Duration>::new()),
+///     )
+///     .render_help()
+///     .to_string();
+///
+/// assert!(help.contains("such as 500ms, 30s, \"1h 30m\" or 2d"));
+/// ```
+pub const DURATION_HELP: &str = "A duration such as 500ms, 30s, \"1h 30m\" or 2d, in units of \
+    y, m (months, when followed by a smaller unit), w, d, h, m (minutes), s, ms, us and ns. A \
+    bare number is seconds.";
+
+/// Parse the value of `arg` as clap's own parsers do, with an error which points at the unknown
+/// unit and lists the standard ones, or at the text which is not a number with a unit. Other
+/// errors from [FancyDuration::parse] are kept as the source of the `clap` error.
+fn parse_value<D>(
+    cmd: &::clap::Command,
+    arg: Option<&::clap::Arg>,
+    value: &std::ffi::OsStr,
+) -> Result<FancyDuration<D>, ::clap::Error>
+where
+    D: AsTimes + Clone + Send + Sync + 'static,
+{
+    let value = value
+        .to_str()
+        .ok_or_else(|| ::clap::Error::new(ErrorKind::InvalidUtf8).with_cmd(cmd))?;
+
+    let invalid = |invalid: &str, unknown_unit: bool| {
+        let mut suffixes: Vec<String> = Vec::new();
+
+        for part in &DURATION_PARTS {
+            if !suffixes.iter().any(|suffix| suffix == part.suffix()) {
+                suffixes.push(part.suffix().to_string());
+            }
+        }
+
+        let mut err = ::clap::Error::new(ErrorKind::InvalidValue).with_cmd(cmd);
+        err.insert(
+            ContextKind::InvalidArg,
+            ContextValue::String(arg.map_or_else(|| "...".to_string(), |arg| arg.to_string())),
+        );
+        err.insert(
+            ContextKind::InvalidValue,
+            ContextValue::String(invalid.to_string()),
+        );
+        if unknown_unit {
+            err.insert(ContextKind::ValidValue, ContextValue::Strings(suffixes));
+        }
+
+        err.insert(
+            ContextKind::Suggested,
+            ContextValue::StyledStrs(vec![StyledStr::from(
+                "durations are numbers with units, such as '30s' or '1h 30m'",
+            )]),
+        );
+        err
+    };
+
+    match check_syntax(value) {
+        Ok(()) => {}
+        Err(SyntaxError::UnknownUnit(unit)) => return Err(invalid(unit, true)),
+        Err(SyntaxError::Invalid(token)) => return Err(invalid(token, false)),
+    }
+
+    (|value: &str| FancyDuration::<D>::parse(value)).parse_ref(cmd, arg, value.as_ref())
+}
+
+/// A [FancyDurationValueParser] parses command line arguments to a [FancyDuration] with
+/// [FancyDuration::parse], and is the default `clap` value parser for [FancyDuration]. Errors
+/// are reported as `clap` reports them, pointing at the unknown unit and listing the standard
+/// units. [DURATION_HELP] describes the syntax in the help for the argument.
+///
+/// ```
+/// use std::time::Duration;
+/// use clap::{Arg, Command};
+/// use fancy_duration::{FancyDuration, FancyDurationValueParser};
+///
+/// let cmd = Command::new("app").arg(
+///     Arg::new("timeout")
+///         .long("timeout")
+///         .value_parser(FancyDurationValueParser::<Duration>::new()),
+/// );
+///
+/// let matches = cmd.clone().get_matches_from(["app", "--timeout", "1h30m"]);
+/// assert_eq!(
+///     matches.get_one::<FancyDuration<Duration>>("timeout").unwrap().duration(),
+///     Duration::new(5400, 0)
+/// );
+///
+/// let err = cmd.try_get_matches_from(["app", "--timeout", "1x"]).unwrap_err();
+/// assert!(err.to_string().contains("invalid value 'x' for '--timeout <timeout>'"));
+/// ```
+pub struct FancyDurationValueParser<D>(PhantomData<fn() -> D>);
+
+impl<D> FancyDurationValueParser<D> {
+    /// Construct the value parser.
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<D> Default for FancyDurationValueParser<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D> Clone for FancyDurationValueParser<D> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl<D> TypedValueParser for FancyDurationValueParser<D>
+where
+    D: AsTimes + Clone + Send + Sync + 'static,
+{
+    type Value = FancyDuration<D>;
+
+    fn parse_ref(
+        &self,
+        cmd: &::clap::Command,
+        arg: Option<&::clap::Arg>,
+        value: &std::ffi::OsStr,
+    ) -> Result<Self::Value, ::clap::Error> {
+        parse_value(cmd, arg, value)
+    }
+}
+
+impl<D> ValueParserFactory for FancyDuration<D>
+where
+    D: AsTimes + Clone + Send + Sync + 'static,
+{
+    type Parser = FancyDurationValueParser<D>;
+
+    fn value_parser() -> Self::Parser {
+        FancyDurationValueParser::new()
+    }
+}
+
+/// A [DurationValueParser] parses command line arguments to a plain duration, such as
+/// [std::time::Duration], as [FancyDurationValueParser] does.
+///
+/// ```
+/// use std::time::Duration;
+/// use clap::{Arg, Command};
+/// use fancy_duration::DurationValueParser;
+///
+/// let matches = Command::new("app")
+///     .arg(
+///         Arg::new("timeout")
+///             .long("timeout")
+///             .value_parser(DurationValueParser::<Duration>::new()),
+///     )
+///     .get_matches_from(["app", "--timeout", "90s"]);
+///
+/// assert_eq!(matches.get_one::<Duration>("timeout"), Some(&Duration::new(90, 0)));
+/// ```
+pub struct DurationValueParser<D>(PhantomData<fn() -> D>);
+
+impl<D> DurationValueParser<D> {
+    /// Construct the value parser.
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<D> Default for DurationValueParser<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D> Clone for DurationValueParser<D> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl<D> TypedValueParser for DurationValueParser<D>
+where
+    D: AsTimes + Clone + Send + Sync + 'static,
+{
+    type Value = D;
+
+    fn parse_ref(
+        &self,
+        cmd: &::clap::Command,
+        arg: Option<&::clap::Arg>,
+        value: &std::ffi::OsStr,
+    ) -> Result<Self::Value, ::clap::Error> {
+        Ok(parse_value::<D>(cmd, arg, value)?.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DurationValueParser, FancyDurationValueParser, DURATION_HELP};
+    use crate::FancyDuration;
+    use clap::{error::ErrorKind, Arg, Command};
+    use std::time::Duration;
+
+    #[test]
+    fn test_value_parser() {
+        let cmd = Command::new("app")
+            .arg(
+                Arg::new("timeout")
+                    .long("timeout")
+                    .help(DURATION_HELP)
+                    .value_parser(DurationValueParser::<Duration>::new()),
+            )
+            .arg(
+                Arg::new("interval")
+                    .long("interval")
+                    .value_parser(clap::value_parser!(FancyDuration<Duration>)),
+            )
+            .arg(
+                Arg::new("delay")
+                    .long("delay")
+                    .value_parser(FancyDurationValueParser::<Duration>::new()),
+            );
+
+        let matches = cmd
+            .clone()
+            .try_get_matches_from(["app", "--timeout", "1m 30s", "--interval", "5s"])
+            .unwrap();
+        assert_eq!(
+            matches.get_one::<Duration>("timeout"),
+            Some(&Duration::new(90, 0))
+        );
+        assert_eq!(
+            matches
+                .get_one::<FancyDuration<Duration>>("interval")
+                .map(|d| d.duration()),
+            Some(Duration::new(5, 0))
+        );
+
+        let err = cmd
+            .clone()
+            .try_get_matches_from(["app", "--delay", "2fortnights"])
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidValue);
+        assert_eq!(
+            err.to_string(),
+            "error: invalid value 'fortnights' for '--delay <delay>'\n  \
+             [possible values: y, m, w, d, h, s, ms, us, ns]\n\n  \
+             tip: durations are numbers with units, such as '30s' or '1h 30m'\n\n\
+             For more information, try '--help'.\n"
+        );
+
+        // text which is not a number with a unit is shown as it is, without the units.
+        for (value, invalid) in [("1.5h", "1.5h"), ("5 s", "5")] {
+            let err = cmd
+                .clone()
+                .try_get_matches_from(["app", "--delay", value])
+                .unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidValue);
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "error: invalid value '{}' for '--delay <delay>'\n\n  \
+                     tip: durations are numbers with units, such as '30s' or '1h 30m'\n\n\
+                     For more information, try '--help'.\n",
+                    invalid
+                ),
+                "{}",
+                value
+            );
+        }
+
+        let err = cmd
+            .clone()
+            .try_get_matches_from(["app", "--timeout", "18446744073709551615m"])
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+        assert_eq!(
+            std::error::Error::source(&err).map(|source| source.to_string()),
+            Some("duration is out of range".to_string())
+        );
+
+        let help = cmd.clone().render_help().to_string();
+        assert!(help.contains("A duration such as 500ms"), "{}", help);
+        assert!(!help.contains("possible values"), "{}", help);
+    }
+}
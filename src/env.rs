@@ -0,0 +1,209 @@
+//! Durations from environment variables, such as `FOO_TIMEOUT=5m`.
+
+use crate::{AsTimes, FancyDuration};
+use std::env::VarError;
+
+impl<D> FancyDuration<D>
+where
+    D: AsTimes + Clone,
+{
+    /// Parse the environment variable `name` with [FancyDuration::parse], or with
+    /// [FancyDuration::parse_iso8601] if it is an ISO 8601 duration such as "PT5M". Unlike
+    /// [FancyDuration::parse], unknown units and other text which is not a number with a unit are
+    /// invalid rather than ignored, so that a typo such as "5x" is reported instead of becoming
+    /// zero.
+    ///
+    /// A variable which is not set is an error which names the variable and can be told apart by
+    /// downcasting it to [VarError::NotPresent]. A variable which is empty or only whitespace is an
+    /// error which says so. An invalid duration, or a negative one for a duration type without a
+    /// sign such as [std::time::Duration], is an error which names the variable and the value.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use fancy_duration::FancyDuration;
+    ///
+    /// std::env::set_var("DOC_FROM_ENV_TIMEOUT", "5m");
+    /// assert_eq!(
+    ///     FancyDuration::<Duration>::from_env("DOC_FROM_ENV_TIMEOUT").unwrap().duration(),
+    ///     Duration::new(300, 0)
+    /// );
+    ///
+    /// std::env::set_var("DOC_FROM_ENV_TIMEOUT", "5x");
+    /// assert_eq!(
+    ///     format!("{:#}", FancyDuration::<Duration>::from_env("DOC_FROM_ENV_TIMEOUT").unwrap_err()),
+    ///     "environment variable DOC_FROM_ENV_TIMEOUT has an invalid duration \"5x\": unknown unit \"x\""
+    /// );
+    ///
+    /// std::env::set_var("DOC_FROM_ENV_TIMEOUT", "");
+    /// assert_eq!(
+    ///     FancyDuration::<Duration>::from_env("DOC_FROM_ENV_TIMEOUT").unwrap_err().to_string(),
+    ///     "environment variable DOC_FROM_ENV_TIMEOUT is empty"
+    /// );
+    /// ```
+    pub fn from_env(name: &str) -> Result<Self, anyhow::Error> {
+        match env_value(name)? {
+            None => Err(anyhow::Error::new(VarError::NotPresent)
+                .context(format!("environment variable {} is not set", name))),
+            Some(value) if value.trim().is_empty() => {
+                Err(anyhow::anyhow!("environment variable {} is empty", name))
+            }
+            Some(value) => Self::parse_env(name, &value),
+        }
+    }
+
+    /// Parse the environment variable `name` as [FancyDuration::from_env] does, yielding
+    /// `default` if it is not set, is empty or is only whitespace. Invalid durations are still an
+    /// error.
+    pub fn from_env_or(name: &str, default: D) -> Result<Self, anyhow::Error> {
+        match env_value(name)? {
+            Some(value) if !value.trim().is_empty() => Self::parse_env(name, &value),
+            _ => Ok(FancyDuration::new(default)),
+        }
+    }
+
+    /// Parse `value`, the value of the environment variable `name`.
+    fn parse_env(name: &str, value: &str) -> Result<Self, anyhow::Error> {
+        Self::parse_strict(value).map_err(|e| {
+            e.context(format!(
+                "environment variable {} has an invalid duration {:?}",
+                name, value
+            ))
+        })
+    }
+}
+
+/// The value of the environment variable `name`, or [None] if it is not set.
+fn env_value(name: &str) -> Result<Option<String>, anyhow::Error> {
+    match std::env::var(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(VarError::NotPresent) => Ok(None),
+        Err(e) => Err(anyhow::Error::new(e).context(format!(
+            "environment variable {} is not valid unicode",
+            name
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FancyDuration;
+    use std::env::VarError;
+    use std::time::Duration;
+
+    #[test]
+    fn test_from_env() {
+        // each case has its own variable, as tests run concurrently.
+        let env_table = [
+            ("FANCY_DURATION_TEST_SET", Some("1h 30m"), Ok(Duration::new(5400, 0))),
+            ("FANCY_DURATION_TEST_BARE", Some("30"), Ok(Duration::new(30, 0))),
+            (
+                "FANCY_DURATION_TEST_UNSET",
+                None,
+                Err("environment variable FANCY_DURATION_TEST_UNSET is not set: environment variable not found"),
+            ),
+            (
+                "FANCY_DURATION_TEST_EMPTY",
+                Some(" "),
+                Err("environment variable FANCY_DURATION_TEST_EMPTY is empty"),
+            ),
+            ("FANCY_DURATION_TEST_ZERO", Some("-0s"), Ok(Duration::ZERO)),
+            ("FANCY_DURATION_TEST_ISO8601", Some("PT1H30M"), Ok(Duration::new(5400, 0))),
+            (
+                "FANCY_DURATION_TEST_ISO8601_INVALID",
+                Some("P1H"),
+                Err("environment variable FANCY_DURATION_TEST_ISO8601_INVALID has an invalid duration \"P1H\": invalid ISO 8601 duration \"P1H\": unexpected or out of order designator"),
+            ),
+            (
+                "FANCY_DURATION_TEST_FRACTION",
+                Some("1.5h"),
+                Err("environment variable FANCY_DURATION_TEST_FRACTION has an invalid duration \"1.5h\": expected numbers with units, but found \"1.5h\""),
+            ),
+            (
+                "FANCY_DURATION_TEST_SPACE",
+                Some("5 s"),
+                Err("environment variable FANCY_DURATION_TEST_SPACE has an invalid duration \"5 s\": expected numbers with units, but found \"5\""),
+            ),
+            (
+                "FANCY_DURATION_TEST_NEGATIVE",
+                Some("-5s"),
+                Err("environment variable FANCY_DURATION_TEST_NEGATIVE has an invalid duration \"-5s\": duration is out of range, as it cannot be negative"),
+            ),
+            (
+                "FANCY_DURATION_TEST_UNIT",
+                Some("5x"),
+                Err("environment variable FANCY_DURATION_TEST_UNIT has an invalid duration \"5x\": unknown unit \"x\""),
+            ),
+            (
+                "FANCY_DURATION_TEST_GARBAGE",
+                Some("soon"),
+                Err("environment variable FANCY_DURATION_TEST_GARBAGE has an invalid duration \"soon\": unknown unit \"soon\""),
+            ),
+            (
+                "FANCY_DURATION_TEST_OVERFLOW",
+                Some("99999999999999999999s"),
+                Err("environment variable FANCY_DURATION_TEST_OVERFLOW has an invalid duration \"99999999999999999999s\": number too large to fit in target type"),
+            ),
+        ];
+
+        for (name, value, expected) in env_table {
+            if let Some(value) = value {
+                std::env::set_var(name, value);
+            }
+
+            let default = Duration::new(1, 0);
+
+            match expected {
+                Ok(duration) => {
+                    assert_eq!(
+                        FancyDuration::<Duration>::from_env(name)
+                            .unwrap()
+                            .duration(),
+                        duration,
+                        "{}",
+                        name
+                    );
+                    assert_eq!(
+                        FancyDuration::from_env_or(name, default)
+                            .unwrap()
+                            .duration(),
+                        duration,
+                        "{}",
+                        name
+                    );
+                }
+                Err(message) => {
+                    let err = FancyDuration::<Duration>::from_env(name).unwrap_err();
+                    assert_eq!(format!("{:#}", err), message, "{}", name);
+
+                    assert_eq!(
+                        matches!(err.downcast_ref(), Some(VarError::NotPresent)),
+                        value.is_none(),
+                        "{}",
+                        name
+                    );
+
+                    // unset and empty variables yield the default.
+                    let missing = value.is_none_or(|v| v.trim().is_empty());
+
+                    match FancyDuration::from_env_or(name, default) {
+                        Ok(duration) if missing => assert_eq!(duration.duration(), default),
+                        Ok(_) => panic!("{} should be invalid", name),
+                        Err(err) => assert_eq!(format!("{:#}", err), message, "{}", name),
+                    }
+                }
+            }
+        }
+
+        // signed durations keep the sign.
+        #[cfg(feature = "jiff")]
+        {
+            std::env::set_var("FANCY_DURATION_TEST_SIGNED", "-5s");
+            assert_eq!(
+                FancyDuration::<jiff::SignedDuration>::from_env("FANCY_DURATION_TEST_SIGNED")
+                    .unwrap()
+                    .duration(),
+                jiff::SignedDuration::from_secs(-5)
+            );
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! ISO 8601 durations, such as "PT1H30M".
+
+use crate::{split_sign, trim_fraction, AsTimes, DurationPart, FancyDuration};
+
+impl<D> FancyDuration<D>
+where
+    D: AsTimes + Clone,
+{
+    /// Supply the ISO 8601 representation of the duration, such as "PT1H30M" or "P1Y2M10DT2H".
+    /// Weeks are included in the days, fractions of a second are included in the seconds, and a
+    /// zero duration is "PT0S". Negative durations are prefixed with "-".
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use fancy_duration::FancyDuration;
+    ///
+    /// assert_eq!(FancyDuration(Duration::new(5400, 0)).format_iso8601(), "PT1H30M");
+    /// assert_eq!(FancyDuration(Duration::new(8 * 24 * 60 * 60, 500)).format_iso8601(), "P8DT0.0000005S");
+    /// assert_eq!(
+    ///     FancyDuration::<Duration>::parse_iso8601("PT1H30M").unwrap().duration(),
+    ///     Duration::new(5400, 0)
+    /// );
+    /// ```
+    pub fn format_iso8601(&self) -> String {
+        let mut s = String::new();
+        self.write_iso8601_to(&mut s)
+            .expect("writing to a String cannot fail");
+        s
+    }
+
+    /// Write the ISO 8601 representation of the duration to `w`, see
+    /// [FancyDuration::format_iso8601].
+    pub fn write_iso8601_to<W: std::fmt::Write>(&self, w: &mut W) -> std::fmt::Result {
+        let breakdown = self.breakdown();
+        let days = breakdown
+            .weeks
+            .saturating_mul(7)
+            .saturating_add(breakdown.days);
+        let subseconds = breakdown.milliseconds * 1_000_000
+            + breakdown.microseconds * 1_000
+            + breakdown.nanoseconds;
+
+        w.write_str(self.sign())?;
+        w.write_char('P')?;
+
+        for (value, designator) in [(breakdown.years, 'Y'), (breakdown.months, 'M'), (days, 'D')] {
+            if value > 0 {
+                write!(w, "{}{}", value, designator)?;
+            }
+        }
+
+        let time = [(breakdown.hours, 'H'), (breakdown.minutes, 'M')];
+
+        if time.iter().all(|(value, _)| *value == 0) && breakdown.seconds == 0 && subseconds == 0 {
+            if breakdown.years == 0 && breakdown.months == 0 && days == 0 {
+                w.write_str("T0S")?;
+            }
+
+            return Ok(());
+        }
+
+        w.write_char('T')?;
+
+        for (value, designator) in time {
+            if value > 0 {
+                write!(w, "{}{}", value, designator)?;
+            }
+        }
+
+        if breakdown.seconds > 0 || subseconds > 0 {
+            write!(w, "{}", breakdown.seconds)?;
+
+            if subseconds > 0 {
+                let (fraction, width) = trim_fraction(subseconds, 9);
+                write!(w, ".{:0width$}", fraction, width = width)?;
+            }
+
+            w.write_char('S')?;
+        }
+
+        Ok(())
+    }
+
+    /// Parse an ISO 8601 duration, such as "PT1H30M" or "P1Y2M10DT2H30M". Designators must
+    /// appear in order, and only the seconds may have a fraction. Negative durations, prefixed
+    /// with "-", are only kept by signed durations.
+    pub fn parse_iso8601(s: &str) -> Result<Self, anyhow::Error> {
+        let (negative, parts) = parse_iso8601_parts(s)?;
+        Ok(FancyDuration::new(
+            D::parse_to_duration("0")?.try_from_parts(negative, &parts)?,
+        ))
+    }
+}
+
+/// Whether `s` looks like an ISO 8601 duration rather than a fancy duration.
+pub(crate) fn is_iso8601(s: &str) -> bool {
+    let (_, s) = split_sign(s);
+    s.trim_start().starts_with(['P', 'p'])
+}
+
+/// Parse an ISO 8601 duration into whether it is negative, and its parts.
+fn parse_iso8601_parts(s: &str) -> Result<(bool, Vec<(DurationPart, u64)>), anyhow::Error> {
+    let invalid = |reason: &str| anyhow::anyhow!("invalid ISO 8601 duration {:?}: {}", s, reason);
+
+    let (negative, rest) = split_sign(s);
+    let rest = rest.trim().to_ascii_uppercase();
+    let rest = rest
+        .strip_prefix('P')
+        .ok_or_else(|| invalid("it must start with P"))?;
+
+    let (date, time) = match rest.split_once('T') {
+        Some((_, "")) => return Err(invalid("there is no time after T")),
+        Some((date, time)) => (date, time),
+        None => (rest, ""),
+    };
+
+    let mut parts = Vec::new();
+
+    for (designators, component) in [
+        (
+            &[
+                ('Y', DurationPart::Years),
+                ('M', DurationPart::Months),
+                ('W', DurationPart::Weeks),
+                ('D', DurationPart::Days),
+            ][..],
+            date,
+        ),
+        (
+            &[
+                ('H', DurationPart::Hours),
+                ('M', DurationPart::Minutes),
+                ('S', DurationPart::Seconds),
+            ][..],
+            time,
+        ),
+    ] {
+        let mut next = 0;
+        let mut number = String::new();
+
+        for c in component.chars() {
+            if c.is_ascii_digit() || c == '.' || c == ',' {
+                number.push(c);
+                continue;
+            }
+
+            let index = designators[next..]
+                .iter()
+                .position(|(designator, _)| *designator == c)
+                .ok_or_else(|| invalid("unexpected or out of order designator"))?;
+            let part = designators[next + index].1.clone();
+            next += index + 1;
+
+            let (whole, fraction) = match number.split_once(['.', ',']) {
+                Some((whole, fraction)) => (whole, Some(fraction)),
+                None => (number.as_str(), None),
+            };
+
+            if whole.is_empty() {
+                return Err(invalid("a designator has no value"));
+            }
+
+            parts.push((part.clone(), whole.parse()?));
+
+            if let Some(fraction) = fraction {
+                if part != DurationPart::Seconds {
+                    return Err(invalid("only seconds may have a fraction"));
+                }
+
+                if fraction.is_empty()
+                    || fraction.len() > 9
+                    || !fraction.bytes().all(|b| b.is_ascii_digit())
+                {
+                    return Err(invalid("the fraction of a second is not 1 to 9 digits"));
+                }
+
+                let ns = fraction.parse::<u64>()? * 10u64.pow(9 - fraction.len() as u32);
+                parts.push((DurationPart::Milliseconds, ns / 1_000_000));
+                parts.push((DurationPart::Microseconds, ns / 1_000 % 1_000));
+                parts.push((DurationPart::Nanoseconds, ns % 1_000));
+            }
+
+            number.clear();
+        }
+
+        if !number.is_empty() {
+            return Err(invalid("a value has no designator"));
+        }
+    }
+
+    if parts.is_empty() {
+        return Err(invalid("there are no values"));
+    }
+
+    Ok((negative, parts))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FancyDuration;
+    use std::time::Duration;
+
+    #[test]
+    fn test_iso8601() {
+        const DAY: u64 = 24 * 60 * 60;
+
+        let iso8601_table = [
+            (Duration::ZERO, "PT0S"),
+            (Duration::new(5400, 0), "PT1H30M"),
+            (Duration::new(59, 0), "PT59S"),
+            (Duration::new(1, 250000000), "PT1.25S"),
+            (Duration::new(0, 1), "PT0.000000001S"),
+            (Duration::new(9 * DAY, 0), "P9D"),
+            (
+                Duration::new(360 * DAY + 30 * DAY + DAY + 3600, 0),
+                "P1Y1M1DT1H",
+            ),
+            (Duration::new(DAY + 61, 0), "P1DT1M1S"),
+        ];
+
+        for (duration, formatted) in iso8601_table {
+            assert_eq!(FancyDuration(duration).format_iso8601(), formatted);
+            assert_eq!(
+                FancyDuration::<Duration>::parse_iso8601(formatted)
+                    .unwrap()
+                    .duration(),
+                duration,
+                "{}",
+                formatted
+            );
+        }
+
+        let parse_table = [
+            ("P1W", Duration::new(7 * DAY, 0)),
+            ("p1w1d", Duration::new(8 * DAY, 0)),
+            ("PT0,5S", Duration::new(0, 500000000)),
+            ("PT36H", Duration::new(36 * 3600, 0)),
+            (" P0D ", Duration::ZERO),
+        ];
+
+        for (s, duration) in parse_table {
+            assert_eq!(
+                FancyDuration::<Duration>::parse_iso8601(s)
+                    .unwrap()
+                    .duration(),
+                duration,
+                "{}",
+                s
+            );
+        }
+
+        for s in [
+            "",
+            "P",
+            "PT",
+            "1H",
+            "P1H",
+            "PT1D",
+            "P1D1Y",
+            "PT1.5H",
+            "PT1.S",
+            "PT1",
+            "P1DT",
+            "PTS",
+            "PT1.1234567891S",
+        ] {
+            assert!(
+                FancyDuration::<Duration>::parse_iso8601(s).is_err(),
+                "{}",
+                s
+            );
+        }
+    }
+}
@@ -0,0 +1,387 @@
+//! Support for the durations of the `jiff` crate: [jiff::SignedDuration], which is a signed
+//! length of time, and [jiff::Span], which keeps its calendar units.
+
+use crate::{
+    out_of_range, parse_parts, split_sign, AsFancyDuration, AsTimes, DurationBreakdown,
+    DurationPart, FancyDuration, ParseFancyDuration,
+};
+
+impl AsTimes for jiff::SignedDuration {
+    fn as_times(&self) -> (u64, u64) {
+        (
+            self.as_secs().unsigned_abs(),
+            self.subsec_nanos().unsigned_abs() as u64,
+        )
+    }
+
+    fn parse_to_duration(s: &str) -> Result<Self, anyhow::Error> {
+        let (negative, s) = split_sign(s);
+        let ns = FancyDuration::<jiff::SignedDuration>::parse_to_ns(s)?;
+        jiff::SignedDuration::ZERO.try_from_signed_times(negative, ns.0, ns.1)
+    }
+
+    /// Saturates at [jiff::SignedDuration::MAX].
+    fn from_times(&self, s: u64, ns: u64) -> Self {
+        self.from_signed_times(false, s, ns)
+    }
+
+    fn is_negative(&self) -> bool {
+        jiff::SignedDuration::is_negative(self)
+    }
+
+    /// Saturates at [jiff::SignedDuration::MIN] and [jiff::SignedDuration::MAX].
+    fn from_signed_times(&self, negative: bool, s: u64, ns: u64) -> Self {
+        self.try_from_signed_times(negative, s, ns)
+            .unwrap_or(if negative {
+                jiff::SignedDuration::MIN
+            } else {
+                jiff::SignedDuration::MAX
+            })
+    }
+
+    fn try_from_signed_times(
+        &self,
+        negative: bool,
+        s: u64,
+        ns: u64,
+    ) -> Result<Self, anyhow::Error> {
+        let duration = jiff::SignedDuration::from_secs(s.try_into().map_err(|_| out_of_range())?)
+            .checked_add(jiff::SignedDuration::from_nanos(
+                ns.try_into().map_err(|_| out_of_range())?,
+            ))
+            .ok_or_else(out_of_range)?;
+
+        Ok(if negative { -duration } else { duration })
+    }
+}
+
+impl ParseFancyDuration<jiff::SignedDuration> for jiff::SignedDuration {
+    fn parse_fancy_duration(s: String) -> Result<Self, anyhow::Error> {
+        Ok(FancyDuration::<jiff::SignedDuration>::parse(&s)?.duration())
+    }
+}
+
+impl AsFancyDuration<jiff::SignedDuration> for jiff::SignedDuration {
+    fn fancy_duration(&self) -> FancyDuration<jiff::SignedDuration> {
+        FancyDuration::new(*self)
+    }
+}
+
+/// The most years a [jiff::Span] may have.
+const MAX_YEARS: i64 = 19_998;
+
+/// Construct a span from the magnitude of its parts, negated if `negative` is set.
+fn span_from_parts(
+    negative: bool,
+    parts: &[(DurationPart, u64)],
+) -> Result<jiff::Span, anyhow::Error> {
+    let breakdown = DurationBreakdown::from_parts(parts);
+
+    let span = jiff::Span::new()
+        .try_years(i64::try_from(breakdown.years)?)?
+        .try_months(i64::try_from(breakdown.months)?)?
+        .try_weeks(i64::try_from(breakdown.weeks)?)?
+        .try_days(i64::try_from(breakdown.days)?)?
+        .try_hours(i64::try_from(breakdown.hours)?)?
+        .try_minutes(i64::try_from(breakdown.minutes)?)?
+        .try_seconds(i64::try_from(breakdown.seconds)?)?
+        .try_milliseconds(i64::try_from(breakdown.milliseconds)?)?
+        .try_microseconds(i64::try_from(breakdown.microseconds)?)?
+        .try_nanoseconds(i64::try_from(breakdown.nanoseconds)?)?;
+
+    Ok(if negative { span.negate() } else { span })
+}
+
+/// A [jiff::Span] keeps its calendar units when parsed and formatted, so "36h" remains 36 hours
+/// rather than becoming "1d 12h", and "1m 0d" remains a month. Its length, e.g. for
+/// [FancyDuration::format_decimal], is according to the default [crate::Calendar].
+///
+/// Parsing and deserializing reject spans beyond the limits of [jiff::Span], such as "20000y",
+/// while the infallible constructors such as [AsTimes::from_parts] saturate at 19,998 years.
+impl AsTimes for jiff::Span {
+    fn as_times(&self) -> (u64, u64) {
+        DurationBreakdown::from_parts(&self.as_parts().unwrap_or_default()).as_times()
+    }
+
+    fn parse_to_duration(s: &str) -> Result<Self, anyhow::Error> {
+        let (negative, s) = split_sign(s);
+        span_from_parts(negative, &parse_parts(s)?)
+    }
+
+    fn from_times(&self, s: u64, ns: u64) -> Self {
+        self.from_signed_times(false, s, ns)
+    }
+
+    fn is_negative(&self) -> bool {
+        self.signum() < 0
+    }
+
+    fn from_signed_times(&self, negative: bool, s: u64, ns: u64) -> Self {
+        self.from_parts(negative, &DurationBreakdown::new(s, ns).parts())
+    }
+
+    fn as_parts(&self) -> Option<Vec<(DurationPart, u64)>> {
+        Some(vec![
+            (DurationPart::Years, self.get_years().unsigned_abs() as u64),
+            (
+                DurationPart::Months,
+                self.get_months().unsigned_abs() as u64,
+            ),
+            (DurationPart::Weeks, self.get_weeks().unsigned_abs() as u64),
+            (DurationPart::Days, self.get_days().unsigned_abs() as u64),
+            (DurationPart::Hours, self.get_hours().unsigned_abs() as u64),
+            (DurationPart::Minutes, self.get_minutes().unsigned_abs()),
+            (DurationPart::Seconds, self.get_seconds().unsigned_abs()),
+            (
+                DurationPart::Milliseconds,
+                self.get_milliseconds().unsigned_abs(),
+            ),
+            (
+                DurationPart::Microseconds,
+                self.get_microseconds().unsigned_abs(),
+            ),
+            (
+                DurationPart::Nanoseconds,
+                self.get_nanoseconds().unsigned_abs(),
+            ),
+        ])
+    }
+
+    fn from_parts(&self, negative: bool, parts: &[(DurationPart, u64)]) -> Self {
+        span_from_parts(negative, parts).unwrap_or_else(|_| {
+            let span = jiff::Span::new().years(MAX_YEARS);
+            if negative {
+                span.negate()
+            } else {
+                span
+            }
+        })
+    }
+
+    fn try_from_signed_times(
+        &self,
+        negative: bool,
+        s: u64,
+        ns: u64,
+    ) -> Result<Self, anyhow::Error> {
+        self.try_from_parts(negative, &DurationBreakdown::new(s, ns).parts())
+    }
+
+    fn try_from_parts(
+        &self,
+        negative: bool,
+        parts: &[(DurationPart, u64)],
+    ) -> Result<Self, anyhow::Error> {
+        span_from_parts(negative, parts)
+    }
+}
+
+impl ParseFancyDuration<jiff::Span> for jiff::Span {
+    fn parse_fancy_duration(s: String) -> Result<Self, anyhow::Error> {
+        Ok(FancyDuration::<jiff::Span>::parse(&s)?.duration())
+    }
+}
+
+impl AsFancyDuration<jiff::Span> for jiff::Span {
+    fn fancy_duration(&self) -> FancyDuration<jiff::Span> {
+        FancyDuration::new(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AsFancyDuration, AsTimes, DurationPart, FancyDuration, ParseFancyDuration};
+    use jiff::{SignedDuration, Span, ToSpan};
+
+    #[test]
+    fn test_signed_duration() {
+        let signed_table = [
+            (SignedDuration::new(185, 0), "3m 5s", "3m5s"),
+            (SignedDuration::new(-185, 0), "-3m 5s", "-3m5s"),
+            (SignedDuration::new(0, -1500), "-1us 500ns", "-1us500ns"),
+            (SignedDuration::ZERO, "0", "0"),
+        ];
+
+        for (duration, formatted, compact) in signed_table {
+            assert_eq!(duration.fancy_duration().to_string(), formatted);
+            assert_eq!(duration.fancy_duration().format_compact(), compact);
+            assert_eq!(
+                SignedDuration::parse_fancy_duration(formatted.to_string()).unwrap(),
+                duration
+            );
+            assert_eq!(
+                FancyDuration::<SignedDuration>::parse(compact)
+                    .unwrap()
+                    .duration(),
+                duration
+            );
+        }
+
+        let duration = FancyDuration(SignedDuration::new(-3723, 0));
+        assert_eq!(duration.truncate(2).to_string(), "-1h 2m");
+        assert_eq!(
+            duration
+                .filter(&[DurationPart::Hours, DurationPart::Seconds])
+                .to_string(),
+            "-1h 3s"
+        );
+        assert_eq!(duration.format_decimal(3), "-1.03h");
+        assert_eq!(
+            FancyDuration::<SignedDuration>::parse_with(" -1h 2m", &crate::DurationFormat::new())
+                .unwrap()
+                .duration(),
+            SignedDuration::new(-3720, 0)
+        );
+
+        for s in [
+            "9223372036854775808s",
+            "-9223372036854775807s 1000ms",
+            "153722867280912931m",
+        ] {
+            assert!(FancyDuration::<SignedDuration>::parse(s).is_err(), "{}", s);
+        }
+        assert_eq!(
+            SignedDuration::ZERO.from_signed_times(true, u64::MAX, 0),
+            SignedDuration::MIN
+        );
+    }
+
+    #[test]
+    fn test_span() {
+        let span_table = [
+            (1.year().months(2).days(3), "1y 2m 3d"),
+            (36.hours(), "36h"),
+            (1.month(), "1m"),
+            (2.weeks().days(9), "2w 9d"),
+            (90.minutes().seconds(30), "90m 30s"),
+            ((-1).day().hours(-2), "-1d 2h"),
+            (1.millisecond().nanoseconds(5), "1ms 5ns"),
+            (Span::new(), "0"),
+        ];
+
+        for (span, formatted) in span_table {
+            assert_eq!(span.fancy_duration().to_string(), formatted);
+        }
+
+        let parse_table = [
+            ("1y 2m 3d", 1.year().months(2).days(3)),
+            ("36h", 36.hours()),
+            ("1m 0d", 1.month()),
+            ("1m", 1.minute()),
+            ("-1d 2h", (-1).day().hours(-2)),
+            ("1h 1h", 2.hours()),
+            ("-30", (-30).seconds()),
+        ];
+
+        for (s, span) in parse_table {
+            assert_eq!(
+                Span::parse_fancy_duration(s.to_string())
+                    .unwrap()
+                    .fieldwise(),
+                span.fieldwise(),
+                "{}",
+                s
+            );
+        }
+
+        assert!(Span::parse_fancy_duration("20000y".to_string()).is_err());
+        assert!(Span::parse_fancy_duration("18446744073709551615s".to_string()).is_err());
+        assert!(
+            FancyDuration::<Span>::parse_with("20000y", &crate::DurationFormat::new()).is_err()
+        );
+        assert_eq!(
+            Span::new().from_times(u64::MAX, 0).fieldwise(),
+            19998.years()
+        );
+
+        // the length of a span is the sum of its units, which may exceed a u64 of nanoseconds.
+        let span = FancyDuration(19998.years().nanoseconds(i64::MAX));
+        assert_eq!(span.duration().as_times(), (631241164036, 854775807));
+        assert_eq!(span.format_decimal_in(DurationPart::Years, 3), "20295y");
+
+        let span = FancyDuration(1.year().months(2).days(3).hours(4));
+        assert_eq!(span.truncate(2).duration().fieldwise(), 1.year().months(2));
+        assert_eq!(span.truncate(2).to_string(), "1y 2m");
+        assert_eq!(
+            span.filter(&[DurationPart::Days, DurationPart::Hours])
+                .duration()
+                .fieldwise(),
+            3.days().hours(4)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_jiff_serde() {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize)]
+        struct JiffDuration {
+            signed: FancyDuration<SignedDuration>,
+            span: FancyDuration<Span>,
+        }
+
+        let json = r#"{"signed":"-1h 30m","span":"36h"}"#;
+        let duration: JiffDuration = serde_json::from_str(json).unwrap();
+        assert_eq!(duration.signed.duration(), SignedDuration::new(-5400, 0));
+        assert_eq!(duration.span.duration().fieldwise(), 36.hours());
+        assert_eq!(serde_json::to_string(&duration).unwrap(), json);
+
+        let span: FancyDuration<Span> = serde_json::from_str(r#"{"h":36,"months":1}"#).unwrap();
+        assert_eq!(span.duration().fieldwise(), 1.month().hours(36));
+        assert!(serde_json::from_str::<FancyDuration<Span>>(r#"{"hours":999999999}"#).is_err());
+
+        // each is too long for a span, and the first two for a signed duration.
+        for json in ["18446744073709551615", "-18446744073709551615", "1e15"] {
+            assert_eq!(
+                serde_json::from_str::<FancyDuration<SignedDuration>>(json).is_err(),
+                json != "1e15",
+                "{}",
+                json
+            );
+            assert!(
+                serde_json::from_str::<FancyDuration<Span>>(json).is_err(),
+                "{}",
+                json
+            );
+        }
+
+        let duration: FancyDuration<SignedDuration> = serde_json::from_str("-90.5").unwrap();
+        assert_eq!(duration.duration(), SignedDuration::new(-90, -500000000));
+
+        assert!(bincode::deserialize::<FancyDuration<SignedDuration>>(
+            &bincode::serialize(&(u64::MAX, 0u32, true)).unwrap()
+        )
+        .is_err());
+        assert!(bincode::deserialize::<FancyDuration<Span>>(
+            &bincode::serialize(&(
+                std::collections::BTreeMap::from([("hours", u64::MAX)]),
+                false
+            ))
+            .unwrap()
+        )
+        .is_err());
+
+        // spans keep their parts.
+        for span in [36.hours(), -(1.month().days(2)), Span::new()] {
+            let encoded = bincode::serialize(&FancyDuration(span)).unwrap();
+            assert_eq!(
+                bincode::deserialize::<FancyDuration<Span>>(&encoded)
+                    .unwrap()
+                    .duration()
+                    .fieldwise(),
+                span.fieldwise(),
+                "{}",
+                span
+            );
+        }
+
+        let encoded = bincode::serialize(&duration).unwrap();
+        assert_eq!(
+            bincode::deserialize::<FancyDuration<SignedDuration>>(&encoded)
+                .unwrap()
+                .duration(),
+            SignedDuration::new(-90, -500000000)
+        );
+    }
+}
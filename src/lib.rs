@@ -9,14 +9,19 @@
 //!   - serde: enables serde support including serialization and deseralization from strings
 //!   - time: enables traits that implement fancy duration features for the `time` crate
 //!   - chrono: enables traits that implement fancy duration features for the `chrono` crate
+//!   - jiff: enables traits that implement fancy duration features for the `jiff` crate, with
+//!     signed durations and spans which keep their calendar units
+//!   - clap: enables value parsers for command line arguments with the `clap` crate
+//!   - schemars: enables JSON Schemas for fancy durations with the `schemars` crate
 //!
 //! What follows are some usage examples. You can either wrap your duration-like type in a
 //! FancyDuration struct, or use types which allow for monkeypatched methods that allow you to work
 //! directly on the target type. For example, use AsFancyDuration to inject fancy_duration calls to
 //! perform the construction (which can be formatted or converted to string) and ParseFancyDuration
 //! to inject parse_fancy_duration constructors to accept strings into your favorite type.
-//! std::time::Duration, time::Duration, and chrono::Duration are all supported (some features may
-//! need to be required) and you can make more types eligible by implementing the AsTimes trait.
+//! std::time::Duration, time::Duration, chrono::Duration, jiff::SignedDuration and jiff::Span are
+//! all supported (some features may need to be required) and you can make more types eligible by
+//! implementing the AsTimes trait.
 //!
 //! ```
 //! use std::time::Duration;
@@ -64,16 +69,55 @@
 //! }
 //! ```
 
+mod approximate;
+mod bounded;
+mod calendar;
+#[cfg(feature = "clap")]
+mod clap_impl;
+mod env;
+mod iso8601;
+#[cfg(feature = "jiff")]
+mod jiff_impl;
+mod relative;
+#[cfg(feature = "schemars")]
+mod schemars_impl;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod time_of_day;
+mod units;
+
+pub use approximate::{ApproximateFormat, Phrase, Threshold};
+pub use bounded::BoundedFancyDuration;
+pub use calendar::{CalendarArithmetic, CalendarDifference, CalendarDuration};
+#[cfg(feature = "clap")]
+pub use clap_impl::{DurationValueParser, FancyDurationValueParser, DURATION_HELP};
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub use relative::ParseRelativeTime;
+pub use relative::RelativeTime;
+pub use time_of_day::FancyTimeOfDay;
+pub use units::{Unit, UnitSystem};
+
 lazy_static::lazy_static! {
     static ref FANCY_FORMAT: regex::Regex = regex::Regex::new(r#"([0-9]+)([a-zA-Z]{1,2})\s*"#).unwrap();
 }
 
 #[cfg(feature = "serde")]
-use serde::{de::Visitor, Deserialize, Serialize};
+use ::serde::{de::Visitor, Deserialize, Serialize};
+use std::fmt::Write;
 #[cfg(feature = "serde")]
 use std::marker::PhantomData;
 use std::time::Duration;
 
+/// The capacity of [FancyDurationString]. This is enough to hold the standard formatted
+/// representation of any duration, including those which keep their parts (see
+/// [AsTimes::as_parts]) with every part at [u64::MAX]: a sign, ten parts of 20 digits, their
+/// suffixes and the whitespace between them.
+pub const FORMAT_CAPACITY: usize = 1 + 10 * 20 + 13 + 9;
+
+/// A fixed-capacity string allocated on the stack, as returned by
+/// [FancyDuration::format_to_array].
+pub type FancyDurationString = arrayvec::ArrayString<FORMAT_CAPACITY>;
+
 /// Implement AsFancyDuration for your Duration type, it will annotate those types with the
 /// `fancy_duration` function which allows trivial and explicit conversion into a fancy duration.
 pub trait AsFancyDuration<T>
@@ -107,7 +151,7 @@ impl ParseFancyDuration<Duration> for Duration {
 
 impl AsFancyDuration<Duration> for Duration {
     fn fancy_duration(&self) -> FancyDuration<Duration> {
-        FancyDuration::new(self.clone())
+        FancyDuration::new(*self)
     }
 }
 
@@ -132,7 +176,7 @@ impl ParseFancyDuration<time::Duration> for time::Duration {
 #[cfg(feature = "time")]
 impl AsFancyDuration<time::Duration> for time::Duration {
     fn fancy_duration(&self) -> FancyDuration<time::Duration> {
-        FancyDuration::new(self.clone())
+        FancyDuration::new(*self)
     }
 }
 
@@ -146,7 +190,7 @@ impl ParseFancyDuration<chrono::Duration> for chrono::Duration {
 #[cfg(feature = "chrono")]
 impl AsFancyDuration<chrono::Duration> for chrono::Duration {
     fn fancy_duration(&self) -> FancyDuration<chrono::Duration> {
-        FancyDuration::new(self.clone())
+        FancyDuration::new(*self)
     }
 }
 
@@ -161,7 +205,62 @@ pub trait AsTimes: Sized {
     /// is the standard parser and provides you with data to construct most duration types.
     fn parse_to_duration(s: &str) -> Result<Self, anyhow::Error>;
     /// Yield one of this implementing duration from a pair of (seconds, nanoseconds).
+    #[allow(clippy::wrong_self_convention)]
     fn from_times(&self, s: u64, ns: u64) -> Self;
+    /// Whether the duration is negative. Signed durations yield their magnitude from
+    /// [AsTimes::as_times], and are formatted with a leading "-" when negative. The default is
+    /// for unsigned durations.
+    fn is_negative(&self) -> bool {
+        false
+    }
+    /// Yield one of this implementing duration from a pair of (seconds, nanoseconds), negated if
+    /// `negative` is set. The default ignores the sign, for unsigned durations.
+    #[allow(clippy::wrong_self_convention)]
+    fn from_signed_times(&self, negative: bool, s: u64, ns: u64) -> Self {
+        let _ = negative;
+        self.from_times(s, ns)
+    }
+    /// The magnitude of each part of the duration, for durations which keep calendar units
+    /// rather than a length, such as `jiff::Span`. These are formatted as they are instead of
+    /// breaking down [AsTimes::as_times]. The default is [None], for durations with a length.
+    fn as_parts(&self) -> Option<Vec<(DurationPart, u64)>> {
+        None
+    }
+    /// Yield one of this implementing duration from the magnitude of its parts, negated if
+    /// `negative` is set. The default adds up the parts with the default [Calendar], see
+    /// [AsTimes::from_signed_times].
+    #[allow(clippy::wrong_self_convention)]
+    fn from_parts(&self, negative: bool, parts: &[(DurationPart, u64)]) -> Self {
+        let times = DurationBreakdown::from_parts(parts).as_times();
+        self.from_signed_times(negative, times.0, times.1)
+    }
+    /// Yield one of this implementing duration as [AsTimes::from_signed_times] does, or an error
+    /// if it is out of range for this duration. This is used for parsed and deserialized input,
+    /// so implementations whose constructors may panic, or which cannot be negative, should
+    /// override it. The default calls [AsTimes::from_signed_times].
+    #[allow(clippy::wrong_self_convention)]
+    fn try_from_signed_times(
+        &self,
+        negative: bool,
+        s: u64,
+        ns: u64,
+    ) -> Result<Self, anyhow::Error> {
+        Ok(self.from_signed_times(negative, s, ns))
+    }
+    /// Yield one of this implementing duration as [AsTimes::from_parts] does, or an error if it
+    /// is out of range for this duration. The default adds up the parts with the default
+    /// [Calendar], see [AsTimes::try_from_signed_times].
+    #[allow(clippy::wrong_self_convention)]
+    fn try_from_parts(
+        &self,
+        negative: bool,
+        parts: &[(DurationPart, u64)],
+    ) -> Result<Self, anyhow::Error> {
+        let times = DurationBreakdown::from_parts(parts)
+            .checked_as_times_with(&Calendar::default())
+            .ok_or_else(out_of_range)?;
+        self.try_from_signed_times(negative, times.0, times.1)
+    }
 }
 
 impl AsTimes for Duration {
@@ -185,6 +284,18 @@ impl AsTimes for Duration {
     fn from_times(&self, s: u64, ns: u64) -> Self {
         Duration::new(s, ns.try_into().unwrap())
     }
+
+    fn try_from_signed_times(
+        &self,
+        negative: bool,
+        s: u64,
+        ns: u64,
+    ) -> Result<Self, anyhow::Error> {
+        check_unsigned(negative, s, ns)?;
+        Duration::from_secs(s)
+            .checked_add(Duration::from_nanos(ns))
+            .ok_or_else(out_of_range)
+    }
 }
 
 #[cfg(feature = "chrono")]
@@ -214,6 +325,18 @@ impl AsTimes for chrono::Duration {
         chrono::TimeDelta::try_seconds(s.try_into().unwrap()).unwrap_or_default()
             + chrono::Duration::nanoseconds(ns.try_into().unwrap())
     }
+
+    fn try_from_signed_times(
+        &self,
+        negative: bool,
+        s: u64,
+        ns: u64,
+    ) -> Result<Self, anyhow::Error> {
+        check_unsigned(negative, s, ns)?;
+        chrono::TimeDelta::try_seconds(s.try_into().map_err(|_| out_of_range())?)
+            .and_then(|d| d.checked_add(&chrono::Duration::nanoseconds(ns.try_into().ok()?)))
+            .ok_or_else(out_of_range)
+    }
 }
 
 #[cfg(feature = "time")]
@@ -233,8 +356,25 @@ impl AsTimes for time::Duration {
     fn from_times(&self, s: u64, ns: u64) -> Self {
         time::Duration::new(s.try_into().unwrap(), ns.try_into().unwrap())
     }
+
+    fn try_from_signed_times(
+        &self,
+        negative: bool,
+        s: u64,
+        ns: u64,
+    ) -> Result<Self, anyhow::Error> {
+        check_unsigned(negative, s, ns)?;
+        time::Duration::seconds(s.try_into().map_err(|_| out_of_range())?)
+            .checked_add(time::Duration::nanoseconds(
+                ns.try_into().map_err(|_| out_of_range())?,
+            ))
+            .ok_or_else(out_of_range)
+    }
 }
 
+/// A [DurationPart] is one of the units of the standard format, from years to nanoseconds. With
+/// serde, parts are serialized by their plural name, e.g. "hours", and deserialized from their
+/// singular or plural name or their suffix, see [DurationPart::from_key].
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum DurationPart {
     Years,
@@ -249,18 +389,121 @@ pub enum DurationPart {
     Nanoseconds,
 }
 
-#[derive(Debug, Clone)]
-pub(crate) struct DurationBreakdown {
-    pub(crate) years: u64,
-    pub(crate) months: u64,
-    pub(crate) weeks: u64,
-    pub(crate) days: u64,
-    pub(crate) hours: u64,
-    pub(crate) minutes: u64,
-    pub(crate) seconds: u64,
-    pub(crate) milliseconds: u64,
-    pub(crate) microseconds: u64,
-    pub(crate) nanoseconds: u64,
+/// All parts in precedence order, largest first.
+const DURATION_PARTS: [DurationPart; 10] = [
+    DurationPart::Years,
+    DurationPart::Months,
+    DurationPart::Weeks,
+    DurationPart::Days,
+    DurationPart::Hours,
+    DurationPart::Minutes,
+    DurationPart::Seconds,
+    DurationPart::Milliseconds,
+    DurationPart::Microseconds,
+    DurationPart::Nanoseconds,
+];
+
+impl DurationPart {
+    /// The timespec used to represent this part in a fancy duration, e.g. "h" for hours.
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            DurationPart::Years => "y",
+            DurationPart::Months => "m",
+            DurationPart::Weeks => "w",
+            DurationPart::Days => "d",
+            DurationPart::Hours => "h",
+            DurationPart::Minutes => "m",
+            DurationPart::Seconds => "s",
+            DurationPart::Milliseconds => "ms",
+            DurationPart::Microseconds => "us",
+            DurationPart::Nanoseconds => "ns",
+        }
+    }
+
+    /// The singular English name of this part, e.g. "hour" for hours. All parts are pluralized by
+    /// appending "s".
+    pub fn name(&self) -> &'static str {
+        match self {
+            DurationPart::Years => "year",
+            DurationPart::Months => "month",
+            DurationPart::Weeks => "week",
+            DurationPart::Days => "day",
+            DurationPart::Hours => "hour",
+            DurationPart::Minutes => "minute",
+            DurationPart::Seconds => "second",
+            DurationPart::Milliseconds => "millisecond",
+            DurationPart::Microseconds => "microsecond",
+            DurationPart::Nanoseconds => "nanosecond",
+        }
+    }
+
+    /// The part named by `key`, which is its singular or plural name, e.g. "hour" or "hours", or
+    /// its suffix, e.g. "h". "m" is minutes; months are only named "month" or "months".
+    pub fn from_key(key: &str) -> Option<Self> {
+        DURATION_PARTS
+            .iter()
+            .find(|part| {
+                key.strip_suffix('s').unwrap_or(key) == part.name()
+                    || (key == part.suffix() && **part != DurationPart::Months)
+            })
+            .cloned()
+    }
+
+    /// The length of one of this part, in nanoseconds.
+    pub(crate) fn as_ns(&self) -> u128 {
+        match self {
+            DurationPart::Years => YEAR as u128 * 1e9 as u128,
+            DurationPart::Months => MONTH as u128 * 1e9 as u128,
+            _ => self.as_ns_in(&Calendar::default()),
+        }
+    }
+
+    /// The length of one of this part in nanoseconds, with years, months, weeks and days according
+    /// to the calendar.
+    pub(crate) fn as_ns_in(&self, calendar: &Calendar) -> u128 {
+        match self {
+            DurationPart::Years => calendar.year as u128 * 1e9 as u128,
+            DurationPart::Months => calendar.month as u128 * 1e9 as u128,
+            DurationPart::Weeks => calendar.week as u128 * 1e9 as u128,
+            DurationPart::Days => calendar.day as u128 * 1e9 as u128,
+            DurationPart::Hours => HOUR as u128 * 1e9 as u128,
+            DurationPart::Minutes => MINUTE as u128 * 1e9 as u128,
+            DurationPart::Seconds => 1e9 as u128,
+            DurationPart::Milliseconds => 1e6 as u128,
+            DurationPart::Microseconds => 1e3 as u128,
+            DurationPart::Nanoseconds => 1,
+        }
+    }
+
+    /// The amount of digits a part is zero-padded to in fixed-width formatting.
+    fn fixed_width(&self) -> usize {
+        match self {
+            DurationPart::Milliseconds | DurationPart::Microseconds | DurationPart::Nanoseconds => {
+                3
+            }
+            _ => 2,
+        }
+    }
+}
+
+/// A [DurationBreakdown] is a duration broken down into a count of each [DurationPart], as it is
+/// formatted, e.g. 1 hour and 30 minutes for "1h 30m". See [FancyDuration::breakdown].
+///
+/// With serde, it is a map from part to count, e.g. `{"hours":1,"minutes":30}`, which omits
+/// zero counts. Keys are read with [DurationPart::from_key], so `{"h":1,"m":30}` is the same, and
+/// unknown or repeated keys are rejected.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DurationBreakdown {
+    pub years: u64,
+    pub months: u64,
+    pub weeks: u64,
+    pub days: u64,
+    pub hours: u64,
+    pub minutes: u64,
+    pub seconds: u64,
+    pub milliseconds: u64,
+    pub microseconds: u64,
+    pub nanoseconds: u64,
 }
 
 const YEAR: u64 = 12 * 30 * 24 * 60 * 60;
@@ -270,16 +513,177 @@ const DAY: u64 = 24 * 60 * 60;
 const HOUR: u64 = 60 * 60;
 const MINUTE: u64 = 60;
 
+/// The model used to determine the length of a year, see [Calendar].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CalendarModel {
+    /// A 360 day year of twelve 30 day months. This is the default.
+    Days360,
+    /// A 365 day year.
+    Days365,
+    /// The Julian year of 365.25 days.
+    Julian,
+    /// The Gregorian year of 365.2425 days.
+    Gregorian,
+}
+
+impl CalendarModel {
+    /// The length of a year in this model, in seconds.
+    pub const fn year_seconds(&self) -> u64 {
+        match self {
+            CalendarModel::Days360 => 360 * DAY,
+            CalendarModel::Days365 => 365 * DAY,
+            CalendarModel::Julian => 36525 * DAY / 100,
+            CalendarModel::Gregorian => 3652425 * DAY / 10000,
+        }
+    }
+}
+
+/// A [Calendar] determines the length of years and months, which otherwise vary depending on
+/// when they occur. Months default to a twelfth of the year, so with the default
+/// [CalendarModel::Days360] a year is 360 days and a month is 30 days, while with
+/// [CalendarModel::Gregorian] a year is 365.2425 days and a month is 30.436875 days.
+///
+/// Weeks and days are seven days and 24 hours, unless a business calendar is used (see
+/// [Calendar::business]) to count working time, where e.g. a week is 5 days of 8 hours.
+///
+/// Supply a calendar to a [DurationFormat] to use it for parsing and formatting.
+///
+/// ```
+/// use std::time::Duration;
+/// use fancy_duration::{Calendar, DurationFormat, FancyDuration};
+///
+/// // Jira style estimates, where a week is 5 days and a day is 8 hours.
+/// let format = DurationFormat::new().calendar(Calendar::business(8, 5));
+/// let estimate = FancyDuration::<Duration>::parse_with("1w 2d 3h", &format).unwrap();
+///
+/// assert_eq!(estimate.duration(), Duration::new(59 * 60 * 60, 0));
+/// assert_eq!(estimate.format_with(&format), "1w 2d 3h");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Calendar {
+    year: u64,
+    month: u64,
+    week: u64,
+    day: u64,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self::new(CalendarModel::Days360)
+    }
+}
+
+impl From<CalendarModel> for Calendar {
+    fn from(model: CalendarModel) -> Self {
+        Self::new(model)
+    }
+}
+
+impl Calendar {
+    /// Construct a calendar from the model, with months being a twelfth of the year.
+    pub const fn new(model: CalendarModel) -> Self {
+        let year = model.year_seconds();
+
+        Self {
+            year,
+            month: year / 12,
+            week: WEEK,
+            day: DAY,
+        }
+    }
+
+    /// Construct a business calendar counting working time, with days of `hours_per_day` hours
+    /// and weeks of `days_per_week` days, e.g. `Calendar::business(8, 5)` for the estimates used
+    /// by Jira. A year is 52 working weeks and months are a twelfth of the year. Zero values are
+    /// treated as one.
+    pub const fn business(hours_per_day: u64, days_per_week: u64) -> Self {
+        let day = if hours_per_day == 0 { 1 } else { hours_per_day } * HOUR;
+        let week = if days_per_week == 0 { 1 } else { days_per_week } * day;
+        let year = 52 * week;
+
+        Self {
+            year,
+            month: year / 12,
+            week,
+            day,
+        }
+    }
+
+    /// Set the length of a month, in seconds. A length of zero is treated as one second.
+    pub fn with_month_seconds(mut self, seconds: u64) -> Self {
+        self.month = seconds.max(1);
+        self
+    }
+
+    /// Set the length of a week, in seconds. A length of zero is treated as one second.
+    pub fn with_week_seconds(mut self, seconds: u64) -> Self {
+        self.week = seconds.max(1);
+        self
+    }
+
+    /// Set the length of a day, in seconds. A length of zero is treated as one second.
+    pub fn with_day_seconds(mut self, seconds: u64) -> Self {
+        self.day = seconds.max(1);
+        self
+    }
+
+    /// The length of a year, in seconds.
+    pub fn year_seconds(&self) -> u64 {
+        self.year
+    }
+
+    /// The length of a month, in seconds.
+    pub fn month_seconds(&self) -> u64 {
+        self.month
+    }
+
+    /// The length of a week, in seconds.
+    pub fn week_seconds(&self) -> u64 {
+        self.week
+    }
+
+    /// The length of a day, in seconds.
+    pub fn day_seconds(&self) -> u64 {
+        self.day
+    }
+}
+
 impl DurationBreakdown {
-    pub(crate) fn new(mut s: u64, mut ns: u64) -> Self {
-        let years = s / YEAR;
-        s -= years * YEAR;
-        let months = s / MONTH;
-        s -= months * MONTH;
-        let weeks = s / WEEK;
-        s -= weeks * WEEK;
-        let days = s / DAY;
-        s -= days * DAY;
+    /// Break down a pair of (seconds, nanoseconds) according to the default [Calendar].
+    pub fn new(s: u64, ns: u64) -> Self {
+        Self::with_calendar(s, ns, &Calendar::default())
+    }
+
+    /// Construct a breakdown from parts as they are, adding up repeated parts.
+    pub(crate) fn from_parts(parts: &[(DurationPart, u64)]) -> Self {
+        let mut obj = Self::new(0, 0);
+
+        for (part, value) in parts {
+            let field = obj.get_mut(part);
+            *field = field.saturating_add(*value);
+        }
+
+        obj
+    }
+
+    /// The non-zero parts, largest first.
+    pub fn parts(&self) -> Vec<(DurationPart, u64)> {
+        DURATION_PARTS
+            .iter()
+            .map(|part| (part.clone(), self.get(part)))
+            .filter(|(_, value)| *value > 0)
+            .collect()
+    }
+
+    pub(crate) fn with_calendar(mut s: u64, mut ns: u64, calendar: &Calendar) -> Self {
+        let years = s / calendar.year;
+        s -= years * calendar.year;
+        let months = s / calendar.month;
+        s -= months * calendar.month;
+        let weeks = s / calendar.week;
+        s -= weeks * calendar.week;
+        let days = s / calendar.day;
+        s -= days * calendar.day;
         let hours = s / HOUR;
         s -= hours * HOUR;
         let minutes = s / MINUTE;
@@ -304,6 +708,34 @@ impl DurationBreakdown {
         }
     }
 
+    /// Break down the duration into only the parts between `max` and `min`, inclusive. Any time
+    /// above `max` is rolled into it rather than into larger parts, so with a `max` of hours, 49
+    /// hours remains 49 hours instead of 2 days and 1 hour. Any time below `min` is dropped.
+    pub(crate) fn new_ranged(
+        s: u64,
+        ns: u64,
+        max: &DurationPart,
+        min: &DurationPart,
+        calendar: &Calendar,
+    ) -> Self {
+        let (max, min) = if max <= min { (max, min) } else { (min, max) };
+
+        let mut obj = Self::new(0, 0);
+        let mut total = s as u128 * 1e9 as u128 + ns as u128;
+
+        for part in DURATION_PARTS
+            .iter()
+            .filter(|part| *part >= max && *part <= min)
+        {
+            let size = part.as_ns_in(calendar);
+            let value = total / size;
+            total -= value * size;
+            *obj.get_mut(part) = value.try_into().unwrap_or(u64::MAX);
+        }
+
+        obj
+    }
+
     pub(crate) fn truncate(&self, mut limit: usize) -> Self {
         let mut obj = self.clone();
         let mut limit_started = false;
@@ -327,9 +759,7 @@ impl DurationBreakdown {
                     *val = 0
                 }
 
-                if limit != 0 {
-                    limit -= 1;
-                }
+                limit = limit.saturating_sub(1);
             }
         }
 
@@ -339,20 +769,7 @@ impl DurationBreakdown {
     pub fn filter(&self, filter: &[DurationPart]) -> Self {
         let mut obj = self.clone();
 
-        let all = &[
-            DurationPart::Years,
-            DurationPart::Months,
-            DurationPart::Weeks,
-            DurationPart::Days,
-            DurationPart::Hours,
-            DurationPart::Minutes,
-            DurationPart::Seconds,
-            DurationPart::Milliseconds,
-            DurationPart::Microseconds,
-            DurationPart::Nanoseconds,
-        ];
-
-        for part in all {
+        for part in &DURATION_PARTS {
             if !filter.contains(part) {
                 match part {
                     DurationPart::Years => obj.years = 0,
@@ -372,20 +789,318 @@ impl DurationBreakdown {
         obj
     }
 
+    /// The count of `part`.
+    pub fn get(&self, part: &DurationPart) -> u64 {
+        match part {
+            DurationPart::Years => self.years,
+            DurationPart::Months => self.months,
+            DurationPart::Weeks => self.weeks,
+            DurationPart::Days => self.days,
+            DurationPart::Hours => self.hours,
+            DurationPart::Minutes => self.minutes,
+            DurationPart::Seconds => self.seconds,
+            DurationPart::Milliseconds => self.milliseconds,
+            DurationPart::Microseconds => self.microseconds,
+            DurationPart::Nanoseconds => self.nanoseconds,
+        }
+    }
+
+    fn get_mut(&mut self, part: &DurationPart) -> &mut u64 {
+        match part {
+            DurationPart::Years => &mut self.years,
+            DurationPart::Months => &mut self.months,
+            DurationPart::Weeks => &mut self.weeks,
+            DurationPart::Days => &mut self.days,
+            DurationPart::Hours => &mut self.hours,
+            DurationPart::Minutes => &mut self.minutes,
+            DurationPart::Seconds => &mut self.seconds,
+            DurationPart::Milliseconds => &mut self.milliseconds,
+            DurationPart::Microseconds => &mut self.microseconds,
+            DurationPart::Nanoseconds => &mut self.nanoseconds,
+        }
+    }
+
+    /// Write the non-zero parts to `w`, separated by whitespace if `pad` is set. An empty
+    /// breakdown is written as "0".
+    pub(crate) fn write_to<W: std::fmt::Write>(&self, w: &mut W, pad: bool) -> std::fmt::Result {
+        let mut empty = true;
+
+        for part in &DURATION_PARTS {
+            let value = self.get(part);
+
+            if value > 0 {
+                if pad && !empty {
+                    w.write_char(' ')?;
+                }
+
+                write!(w, "{}{}", value, part.suffix())?;
+                empty = false;
+            }
+        }
+
+        if empty {
+            w.write_char('0')?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the non-zero parts to `w` with their English names, e.g. "1 hour 30 minutes". An
+    /// empty breakdown is written as "0 seconds".
+    pub(crate) fn write_long_to<W: std::fmt::Write>(&self, w: &mut W) -> std::fmt::Result {
+        let mut empty = true;
+
+        for part in &DURATION_PARTS {
+            let value = self.get(part);
+
+            if value > 0 {
+                if !empty {
+                    w.write_char(' ')?;
+                }
+
+                write!(w, "{} {}", value, part.name())?;
+
+                if value != 1 {
+                    w.write_char('s')?;
+                }
+
+                empty = false;
+            }
+        }
+
+        if empty {
+            w.write_str("0 seconds")?;
+        }
+
+        Ok(())
+    }
+
+    /// The total of the parts as a pair of (seconds, nanoseconds) according to the default
+    /// [Calendar], saturating at [u64::MAX] seconds.
     pub fn as_times(&self) -> (u64, u64) {
-        let mut s = 0;
-        let mut ns = 0;
+        self.as_times_with(&Calendar::default())
+    }
+
+    /// The total of the parts as a pair of (seconds, nanoseconds) according to the calendar,
+    /// saturating at [u64::MAX] seconds.
+    pub fn as_times_with(&self, calendar: &Calendar) -> (u64, u64) {
+        self.checked_as_times_with(calendar)
+            .unwrap_or((u64::MAX, 999_999_999))
+    }
+
+    /// The total of the parts as a pair of (seconds, nanoseconds) according to the calendar, or
+    /// [None] if it overflows.
+    pub(crate) fn checked_as_times_with(&self, calendar: &Calendar) -> Option<(u64, u64)> {
+        let mut total: u128 = 0;
+
+        for part in &DURATION_PARTS {
+            total = total
+                .checked_add((self.get(part) as u128).checked_mul(part.as_ns_in(calendar))?)?;
+        }
+
+        split_ns(total).ok()
+    }
+}
+
+/// A [DurationFormat] describes how a [FancyDuration] is formatted with
+/// [FancyDuration::format_with] and parsed with [FancyDuration::parse_with]. The default is the
+/// standard format, which is what [FancyDuration::format] and [FancyDuration::parse] use.
+///
+/// ```
+/// use std::time::Duration;
+/// use fancy_duration::{DurationFormat, DurationPart, FancyDuration};
+///
+/// let format = DurationFormat::new().max_unit(DurationPart::Hours);
+/// assert_eq!(FancyDuration(Duration::new(49 * 60 * 60, 0)).format_with(&format), "49h");
+///
+/// let format = DurationFormat::new().max_unit(DurationPart::Milliseconds).compact(true);
+/// assert_eq!(FancyDuration(Duration::new(2, 5000)).format_with(&format), "2000ms5us");
+///
+/// let format = DurationFormat::new()
+///     .alias("sprint", Duration::new(14 * 24 * 60 * 60, 0))
+///     .alias("tick", Duration::from_millis(50));
+/// let duration = FancyDuration::<Duration>::parse_with("1sprint 2d 3tick", &format).unwrap();
+/// assert_eq!(duration.format_with(&format), "2w 2d 150ms");
+/// assert_eq!(duration.format_with(&format.prefer_aliases(true)), "1sprint 2d 3tick");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DurationFormat {
+    compact: bool,
+    max_unit: DurationPart,
+    min_unit: DurationPart,
+    calendar: Calendar,
+    units: Option<Vec<Unit>>,
+    aliases: Vec<Unit>,
+    prefer_aliases: bool,
+    default_unit: DurationPart,
+}
+
+impl Default for DurationFormat {
+    fn default() -> Self {
+        Self {
+            compact: false,
+            max_unit: DurationPart::Years,
+            min_unit: DurationPart::Nanoseconds,
+            calendar: Calendar::default(),
+            units: None,
+            aliases: Vec::new(),
+            prefer_aliases: false,
+            default_unit: DurationPart::Seconds,
+        }
+    }
+}
+
+impl DurationFormat {
+    /// Construct the standard format.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove the whitespace between units, as [FancyDuration::format_compact] does.
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    /// The largest unit to represent. Time that would otherwise be expressed in larger units is
+    /// rolled into this one, e.g. "2d 1h" becomes "49h" with a maximum of hours.
+    pub fn max_unit(mut self, part: DurationPart) -> Self {
+        self.max_unit = part;
+        self
+    }
+
+    /// The smallest unit to represent. Time below this unit is dropped, e.g. "1h 2m 3s" becomes
+    /// "1h 2m" with a minimum of minutes.
+    pub fn min_unit(mut self, part: DurationPart) -> Self {
+        self.min_unit = part;
+        self
+    }
+
+    /// The [Calendar] which determines the length of years, months, weeks and days, for both
+    /// parsing and formatting.
+    pub fn calendar(mut self, calendar: impl Into<Calendar>) -> Self {
+        self.calendar = calendar.into();
+        self
+    }
+
+    /// The [UnitSystem] to parse and format with instead of the standard units. The maximum and
+    /// minimum units and the calendar only apply to the standard units, as a unit system selects
+    /// its own units. Only [FancyDuration::parse_with], [FancyDuration::format_with] and
+    /// [FancyDuration::write_with] use the unit system, see [UnitSystem].
+    pub fn unit_system(mut self, system: &(impl UnitSystem + ?Sized)) -> Self {
+        let mut units = system.units();
+        units.sort_by_key(|unit| std::cmp::Reverse(unit.as_ns()));
+        self.units = Some(units);
+        self
+    }
+
+    /// Register `suffix` as an alias for `length` when parsing, e.g. "sprint" for two weeks or
+    /// "tick" for 50 milliseconds. Aliases are only used for formatting when preferred, see
+    /// [DurationFormat::prefer_aliases]. Suffixes are letters only.
+    pub fn alias(mut self, suffix: &str, length: Duration) -> Self {
+        self.aliases
+            .push(Unit::new(suffix, &[suffix], length.as_nanos()));
+        self
+    }
+
+    /// Format with the registered aliases in preference to units of the same or a smaller
+    /// length, e.g. "1sprint 2d" instead of "2w 2d". With the standard units, aliases longer than
+    /// the maximum unit or shorter than the minimum unit are not used.
+    pub fn prefer_aliases(mut self, prefer: bool) -> Self {
+        self.prefer_aliases = prefer;
+        self
+    }
+
+    /// The unit of a bare number such as "30" or "1.5" when parsing, which is seconds by
+    /// default. Its length is according to the calendar.
+    pub fn default_unit(mut self, part: DurationPart) -> Self {
+        self.default_unit = part;
+        self
+    }
+
+    /// The units to parse with, or format with if `formatting` is set, including any aliases.
+    /// [None] selects the standard units.
+    fn units_in_use(&self, formatting: bool) -> Option<std::borrow::Cow<'_, [Unit]>> {
+        if self.aliases.is_empty() || (formatting && !self.prefer_aliases) {
+            return self.units.as_deref().map(std::borrow::Cow::Borrowed);
+        }
+
+        // aliases come first so that they are preferred over units of the same length.
+        let mut units = self.aliases.clone();
+
+        match &self.units {
+            Some(base) => units.extend(base.iter().cloned()),
+            None if formatting => {
+                let (max, min) = if self.max_unit <= self.min_unit {
+                    (&self.max_unit, &self.min_unit)
+                } else {
+                    (&self.min_unit, &self.max_unit)
+                };
+                let range = min.as_ns_in(&self.calendar)..=max.as_ns_in(&self.calendar);
+                units.retain(|unit| range.contains(&unit.as_ns()));
+
+                units.extend(
+                    DURATION_PARTS
+                        .iter()
+                        .zip(self.calendar.units())
+                        .filter(|(part, _)| *part >= max && *part <= min)
+                        .map(|(_, unit)| unit),
+                );
+            }
+            None => units.extend(self.calendar.units()),
+        }
+
+        units.sort_by_key(|unit| std::cmp::Reverse(unit.as_ns()));
+
+        Some(std::borrow::Cow::Owned(units))
+    }
+
+    /// Parse `s` to a tuple of (seconds, nanoseconds).
+    pub(crate) fn parse_to_ns(&self, s: &str) -> Result<(u64, u64), anyhow::Error> {
+        if let Some(total) = parse_number(s, self.default_unit.as_ns_in(&self.calendar)) {
+            return split_ns(total?);
+        }
+
+        let units = match self.units_in_use(false) {
+            Some(units) => units,
+            None => return FancyDuration::<Duration>::parse_to_ns_with(s, &self.calendar),
+        };
+
+        let mut total: u128 = 0;
+
+        for (index, value) in units::parse_units(s, &units::UNIT_FORMAT, &units)? {
+            total = (value as u128)
+                .checked_mul(units[index].as_ns())
+                .and_then(|ns| total.checked_add(ns))
+                .ok_or_else(out_of_range)?;
+        }
+
+        split_ns(total)
+    }
 
-        s += self.years * 12 * 30 * 24 * 60 * 60
-            + self.months * 30 * 24 * 60 * 60
-            + self.weeks * 7 * 24 * 60 * 60
-            + self.days * 24 * 60 * 60
-            + self.hours * 60 * 60
-            + self.minutes * 60
-            + self.seconds;
-        ns += self.milliseconds * 1e6 as u64 + self.microseconds * 1e3 as u64 + self.nanoseconds;
+    /// Write the (seconds, nanoseconds) pair to `w`.
+    pub(crate) fn write_to<W: std::fmt::Write>(
+        &self,
+        w: &mut W,
+        times: (u64, u64),
+    ) -> std::fmt::Result {
+        match self.units_in_use(true) {
+            Some(units) => units::write_units(
+                w,
+                units.iter(),
+                times.0 as u128 * 1e9 as u128 + times.1 as u128,
+                !self.compact,
+            ),
+            None => self.breakdown(times.0, times.1).write_to(w, !self.compact),
+        }
+    }
 
-        (s, ns)
+    pub(crate) fn breakdown(&self, s: u64, ns: u64) -> DurationBreakdown {
+        if self.max_unit == DurationPart::Years && self.min_unit == DurationPart::Nanoseconds {
+            DurationBreakdown::with_calendar(s, ns, &self.calendar)
+        } else {
+            DurationBreakdown::new_ranged(s, ns, &self.max_unit, &self.min_unit, &self.calendar)
+        }
     }
 }
 
@@ -430,11 +1145,13 @@ impl DurationBreakdown {
 ///
 /// Some time units have been simplified:
 ///
-/// - Years is 365 days
+/// - Years is 360 days
 /// - Months is 30 days
 ///
 /// These durations do not account for variations in the potential unit based on the current time.
-/// Perhaps in a future release.
+/// A different [Calendar], such as a 365 day or Gregorian year, can be selected with
+/// [DurationFormat::calendar]. Entirely different units, such as decades or sprints, can be
+/// selected with [DurationFormat::unit_system].
 ///
 #[derive(Clone, Debug, PartialEq)]
 pub struct FancyDuration<D: AsTimes + Clone>(pub D);
@@ -463,11 +1180,8 @@ where
     /// as if they didn't exist.
     pub fn filter(&self, filter: &[DurationPart]) -> Self {
         let mut obj = self.clone();
-        let times = self.0.as_times();
-        let filtered = DurationBreakdown::new(times.0, times.1)
-            .filter(filter)
-            .as_times();
-        obj.0 = self.0.from_times(filtered.0, filtered.1);
+        let filtered = self.breakdown().filter(filter);
+        obj.0 = self.0.from_parts(self.0.is_negative(), &filtered.parts());
         obj
     }
 
@@ -478,18 +1192,36 @@ where
     /// counted. "1h 2m 3s" would truncate to 3 with "1h 2m 3s".
     pub fn truncate(&self, limit: usize) -> Self {
         let mut obj = self.clone();
-        let times = self.0.as_times();
-        let truncated = DurationBreakdown::new(times.0, times.1)
-            .truncate(limit)
-            .as_times();
-        obj.0 = self.0.from_times(truncated.0, truncated.1);
+        let truncated = self.breakdown().truncate(limit);
+        obj.0 = self.0.from_parts(self.0.is_negative(), &truncated.parts());
         obj
     }
 
     /// Parse a string that contains a human-readable duration. See [FancyDuration] for more
     /// information on how times are represented.
     pub fn parse(s: &str) -> Result<Self, anyhow::Error> {
-        Ok(FancyDuration::new(D::parse_to_duration(s)?))
+        Ok(FancyDuration::new(apply_sign(s, D::parse_to_duration(s)?)?))
+    }
+
+    /// Parse a string that contains a human-readable duration according to `format`, see
+    /// [DurationFormat].
+    pub fn parse_with(s: &str, format: &DurationFormat) -> Result<Self, anyhow::Error> {
+        let (negative, s) = split_sign(s);
+        Ok(FancyDuration::new(from_parsed(
+            negative,
+            format.parse_to_ns(s)?,
+        )?))
+    }
+
+    /// Parse an ISO 8601 duration, or a string in fancy duration format with only numbers and
+    /// standard units, see [check_syntax]. This is how strings are deserialized.
+    pub(crate) fn parse_strict(s: &str) -> Result<Self, anyhow::Error> {
+        if iso8601::is_iso8601(s) {
+            return Self::parse_iso8601(s);
+        }
+
+        check_syntax(s).map_err(|e| anyhow::anyhow!("{}", e))?;
+        Self::parse(s)
     }
 
     /// Supply the standard formatted human-readable representation of the duration. This format
@@ -504,144 +1236,474 @@ where
         self.format_internal(false)
     }
 
-    fn format_internal(&self, pad: bool) -> String {
-        let times = self.0.as_times();
+    /// Write the standard formatted representation of the duration to `w`. Unlike
+    /// [FancyDuration::format], this does not allocate, so it is suitable for hot paths such as
+    /// logging. [std::fmt::Display] is implemented in terms of this method.
+    pub fn write_to<W: std::fmt::Write>(&self, w: &mut W) -> std::fmt::Result {
+        self.write_internal(w, true, None)
+    }
 
-        if times.0 == 0 && times.1 == 0 {
-            return "0".to_string();
-        }
+    /// Write the compact formatted representation of the duration to `w` without allocating.
+    pub fn write_compact_to<W: std::fmt::Write>(&self, w: &mut W) -> std::fmt::Result {
+        self.write_internal(w, false, None)
+    }
 
-        let breakdown = DurationBreakdown::new(times.0, times.1);
+    /// Supply the standard formatted representation of the duration in a fixed-capacity string
+    /// that lives on the stack, avoiding allocation. It can be used anywhere a `&str` can.
+    pub fn format_to_array(&self) -> FancyDurationString {
+        let mut s = FancyDurationString::new();
+        self.write_to(&mut s)
+            .expect("formatted duration exceeds FORMAT_CAPACITY");
+        s
+    }
 
+    /// Supply the representation of the duration described by `format`. See [DurationFormat].
+    pub fn format_with(&self, format: &DurationFormat) -> String {
         let mut s = String::new();
+        self.write_with(&mut s, format)
+            .expect("writing to a String cannot fail");
+        s
+    }
 
-        let spad = if pad { " " } else { "" };
+    /// Write the representation of the duration described by `format` to `w` without allocating.
+    pub fn write_with<W: std::fmt::Write>(
+        &self,
+        w: &mut W,
+        format: &DurationFormat,
+    ) -> std::fmt::Result {
+        w.write_str(self.sign())?;
+        format.write_to(w, self.0.as_times())
+    }
 
-        if breakdown.years > 0 {
-            s += &format!("{}y{}", breakdown.years, spad)
-        }
+    fn format_internal(&self, pad: bool) -> String {
+        let mut s = String::new();
+        self.write_internal(&mut s, pad, None)
+            .expect("writing to a String cannot fail");
+        s
+    }
 
-        if breakdown.months > 0 {
-            s += &format!("{}m{}", breakdown.months, spad)
-        }
+    fn write_internal<W: std::fmt::Write>(
+        &self,
+        w: &mut W,
+        pad: bool,
+        limit: Option<usize>,
+    ) -> std::fmt::Result {
+        let mut breakdown = self.breakdown();
 
-        if breakdown.weeks > 0 {
-            s += &format!("{}w{}", breakdown.weeks, spad)
+        if let Some(limit) = limit {
+            breakdown = breakdown.truncate(limit);
         }
 
-        if breakdown.days > 0 {
-            s += &format!("{}d{}", breakdown.days, spad)
-        }
+        w.write_str(self.sign())?;
+        breakdown.write_to(w, pad)
+    }
 
-        if breakdown.hours > 0 {
-            s += &format!("{}h{}", breakdown.hours, spad)
+    /// Break down the duration into the count of each part, as it is formatted. Durations which
+    /// keep their parts, see [AsTimes::as_parts], are broken down as they are. The sign is not
+    /// included.
+    pub fn breakdown(&self) -> DurationBreakdown {
+        match self.0.as_parts() {
+            Some(parts) => DurationBreakdown::from_parts(&parts),
+            None => {
+                let times = self.0.as_times();
+                DurationBreakdown::new(times.0, times.1)
+            }
         }
+    }
 
-        if breakdown.minutes > 0 {
-            s += &format!("{}m{}", breakdown.minutes, spad)
+    /// The prefix for the sign of the duration.
+    fn sign(&self) -> &'static str {
+        if self.0.is_negative() {
+            "-"
+        } else {
+            ""
         }
+    }
 
-        if breakdown.seconds > 0 {
-            s += &format!("{}s{}", breakdown.seconds, spad)
-        }
+    /// Supply a fixed-width representation of the duration suitable for tabular output. Only the
+    /// supplied parts are represented, and each is always included and zero-padded, e.g. "01h 02m
+    /// 03s" or "00h 03m 05s" when supplied hours, minutes and seconds. Any time above the largest
+    /// part is rolled into it, and any time below the smallest part is dropped, so 26 hours would
+    /// be "26h 00m 00s" instead of a day and two hours.
+    pub fn format_fixed(&self, parts: &[DurationPart]) -> String {
+        let mut parts = parts.to_vec();
+        parts.sort();
+        parts.dedup();
+
+        let mut total = self.total_ns();
+        let mut s = String::new();
+
+        for part in parts {
+            let size = part.as_ns();
+            let value = total / size;
+            total -= value * size;
+
+            if !s.is_empty() {
+                s.push(' ');
+            }
 
-        if breakdown.milliseconds > 0 {
-            s += &format!("{}ms{}", breakdown.milliseconds, spad)
+            write!(
+                s,
+                "{:0width$}{}",
+                value,
+                part.suffix(),
+                width = part.fixed_width()
+            )
+            .expect("writing to a String cannot fail");
         }
 
-        if breakdown.microseconds > 0 {
-            s += &format!("{}us{}", breakdown.microseconds, spad)
+        if self.0.is_negative() {
+            s.insert_str(0, self.sign());
         }
 
-        if breakdown.nanoseconds > 0 {
-            s += &format!("{}ns{}", breakdown.nanoseconds, spad)
+        s
+    }
+
+    /// Supply the duration as a single decimal value, automatically scaled to the largest unit
+    /// that keeps the value at or above one. `precision` is the number of significant digits;
+    /// trailing zeroes are removed. For example, 90 minutes with a precision of 3 is "1.5h", and
+    /// 1234567ns is "1.23ms". The largest unit is weeks, as a lone "1.5m" would be read as
+    /// minutes rather than months; see [FancyDuration::format_decimal_in] for months and years.
+    pub fn format_decimal(&self, precision: usize) -> String {
+        let total = self.total_ns();
+
+        if total == 0 {
+            return "0".to_string();
         }
 
-        if pad {
-            s.truncate(s.len() - 1);
+        // weeks and smaller, see above.
+        for part in &DURATION_PARTS[2..] {
+            let s = format_significant(total as f64 / part.as_ns() as f64, precision);
+            // rounding may carry the value up to one, such as 999.9ms becoming 1s.
+            if s.parse::<f64>().unwrap_or_default() >= 1.0 {
+                return self.sign().to_string() + &s + part.suffix();
+            }
         }
 
-        s
+        unreachable!("nanoseconds are always at least one when the duration is non-zero")
+    }
+
+    /// Supply the duration as a single decimal value in the specified unit, such as "0.25h".
+    /// `precision` is the number of significant digits; see [FancyDuration::format_decimal].
+    pub fn format_decimal_in(&self, part: DurationPart, precision: usize) -> String {
+        self.sign().to_string()
+            + &format_significant(self.total_ns() as f64 / part.as_ns() as f64, precision)
+            + part.suffix()
+    }
+
+    fn total_ns(&self) -> u128 {
+        let times = self.0.as_times();
+        times.0 as u128 * 1e9 as u128 + times.1 as u128
     }
 
     /// Parse a string in fancy duration format to a tuple of (seconds, nanoseconds). Nanoseconds
     /// is simply a subsecond count and does not contain the seconds represented as nanoseconds. If
     /// a parsing error occurs that will appear in the result.
     pub fn parse_to_ns(s: &str) -> Result<(u64, u64), anyhow::Error> {
+        Self::parse_to_ns_with(s, &Calendar::default())
+    }
+
+    /// Parse a string in fancy duration format to a tuple of (seconds, nanoseconds) as
+    /// [FancyDuration::parse_to_ns] does, with years, months, weeks and days according to the
+    /// calendar.
+    pub fn parse_to_ns_with(s: &str, calendar: &Calendar) -> Result<(u64, u64), anyhow::Error> {
         let mut subseconds: u64 = 0;
         let mut seconds: u64 = 0;
-        let mut past_minutes = false;
-
-        let mut list: Vec<(&str, &str)> = Vec::new();
 
-        for item in FANCY_FORMAT.captures_iter(s) {
-            list.push((item.get(1).unwrap().as_str(), item.get(2).unwrap().as_str()));
-        }
-
-        for (value, suffix) in list.iter().rev() {
-            match *suffix {
-                "ns" => {
-                    let result: u64 = value.parse()?;
-                    subseconds += result;
-                }
-                "ms" => {
-                    let result: u64 = value.parse()?;
-                    subseconds += result * 1e6 as u64;
-                }
-                "us" => {
-                    let result: u64 = value.parse()?;
-                    subseconds += result * 1e3 as u64;
-                }
-                "s" => {
-                    let result: u64 = value.parse()?;
-                    seconds += result;
-                }
-                "m" => {
-                    let result: u64 = value.parse()?;
-                    seconds += if past_minutes {
-                        result * 60 * 60 * 24 * 30
-                    } else {
-                        past_minutes = true;
-                        result * 60
-                    }
-                }
-                "h" => {
-                    past_minutes = true;
-                    let result: u64 = value.parse()?;
-                    seconds += result * 60 * 60
-                }
-                "d" => {
-                    past_minutes = true;
-                    let result: u64 = value.parse()?;
-                    seconds += result * 60 * 60 * 24
-                }
-                "w" => {
-                    past_minutes = true;
-                    let result: u64 = value.parse()?;
-                    seconds += result * 60 * 60 * 24 * 7
-                }
-                "y" => {
-                    past_minutes = true;
-                    let result: u64 = value.parse()?;
-                    seconds += result * 12 * 30 * 60 * 60 * 24
-                }
-                _ => {}
-            }
+        for (part, value) in parse_parts(s)? {
+            let size = part.as_ns_in(calendar);
+
+            if size < 1e9 as u128 {
+                subseconds = value
+                    .checked_mul(size as u64)
+                    .and_then(|ns| subseconds.checked_add(ns))
+                    .ok_or_else(out_of_range)?;
+            } else {
+                seconds = value
+                    .checked_mul((size / 1e9 as u128) as u64)
+                    .and_then(|s| seconds.checked_add(s))
+                    .ok_or_else(out_of_range)?;
+            }
         }
 
         Ok((seconds, subseconds))
     }
 }
 
+/// Parse a string in fancy duration format into its parts, in reverse order of appearance. "m"
+/// is minutes unless minutes, or a larger unit, appeared after it, in which case it is months.
+/// Unknown timespecs are ignored, and a bare number such as "30" or "1.5" is seconds.
+pub(crate) fn parse_parts(s: &str) -> Result<Vec<(DurationPart, u64)>, anyhow::Error> {
+    // the sign is left to the caller, as it is for numbers with units.
+    if let Some(total) = parse_number(split_sign(s).1, DurationPart::Seconds.as_ns()) {
+        let (seconds, ns) = split_ns(total?)?;
+
+        return Ok(vec![
+            (DurationPart::Seconds, seconds),
+            (DurationPart::Milliseconds, ns / 1_000_000),
+            (DurationPart::Microseconds, ns / 1_000 % 1_000),
+            (DurationPart::Nanoseconds, ns % 1_000),
+        ]);
+    }
+
+    Ok(
+        units::parse_units(s, &FANCY_FORMAT, &units::STANDARD_UNITS)?
+            .into_iter()
+            .map(|(index, value)| (DURATION_PARTS[index].clone(), value))
+            .collect(),
+    )
+}
+
 impl<D> std::fmt::Display for FancyDuration<D>
 where
     D: AsTimes + Clone,
 {
+    /// Formats the standard representation. The standard formatting flags are honored:
+    ///
+    /// - `{:#}` formats the compact representation, see [FancyDuration::format_compact].
+    /// - `{:.N}` truncates to N significant values, see [FancyDuration::truncate].
+    /// - A width pads the result with the fill character and, like numbers, right-aligns it unless
+    ///   another alignment is requested.
+    ///
+    /// For example, `format!("{:#.2}", duration)` might yield "1h2m" for a duration of "1h 2m 3s".
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pad = !f.alternate();
+        let limit = f.precision();
+
+        if f.width().is_none() {
+            return self.write_internal(f, pad, limit);
+        }
+
+        let mut s = FancyDurationString::new();
+        self.write_internal(&mut s, pad, limit)?;
+        pad_aligned(f, &s)
+    }
+}
+
+/// Construct a duration from the (seconds, nanoseconds) produced by parsing. [AsTimes] only
+/// offers [AsTimes::from_times] on an existing duration, so a zero duration is parsed to start
+/// from.
+fn from_parsed<D: AsTimes>(negative: bool, times: (u64, u64)) -> Result<D, anyhow::Error> {
+    D::parse_to_duration("0")?.try_from_signed_times(negative, times.0, times.1)
+}
+
+/// The error for a duration which is too long for its type.
+pub(crate) fn out_of_range() -> anyhow::Error {
+    anyhow::anyhow!("duration is out of range")
+}
+
+/// An error if a duration of a type which cannot be negative is, see
+/// [AsTimes::try_from_signed_times]. Negative zero is zero.
+fn check_unsigned(negative: bool, s: u64, ns: u64) -> Result<(), anyhow::Error> {
+    if negative && (s, ns) != (0, 0) {
+        return Err(anyhow::anyhow!(
+            "duration is out of range, as it cannot be negative"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Negate `duration`, parsed from `s` with [AsTimes::parse_to_duration], if `s` has a sign which
+/// the parser did not keep. This yields an error for durations which cannot be negative, see
+/// [AsTimes::try_from_signed_times].
+fn apply_sign<D: AsTimes>(s: &str, duration: D) -> Result<D, anyhow::Error> {
+    let (secs, ns) = duration.as_times();
+
+    if !split_sign(s).0 || duration.is_negative() || (secs, ns) == (0, 0) {
+        return Ok(duration);
+    }
+
+    duration.try_from_signed_times(true, secs, ns)
+}
+
+/// Parse a bare number such as "30" or "1.5" in units of `unit_ns` nanoseconds, to nanoseconds.
+/// [None] if `s` is not a bare number. Fractions below a nanosecond are dropped.
+pub(crate) fn parse_number(s: &str, unit_ns: u128) -> Option<Result<u128, anyhow::Error>> {
+    let s = s.trim();
+    let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+
+    if whole.is_empty()
+        || !whole.bytes().all(|b| b.is_ascii_digit())
+        || !fraction.bytes().all(|b| b.is_ascii_digit())
+        || s.ends_with('.')
+    {
+        return None;
+    }
+
+    // digits beyond 18 are well below a nanosecond for any unit, and would overflow.
+    let fraction = &fraction[..fraction.len().min(18)];
+
+    Some((|| {
+        let mut total = whole.parse::<u64>()? as u128 * unit_ns;
+
+        if !fraction.is_empty() {
+            total += fraction.parse::<u128>()? * unit_ns / 10u128.pow(fraction.len() as u32);
+        }
+
+        Ok(total)
+    })())
+}
+
+/// Why a string is not strictly a fancy duration, see [check_syntax].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SyntaxError<'a> {
+    /// A suffix which is not a standard unit, such as "x" in "5x", or a word such as "soon".
+    UnknownUnit(&'a str),
+    /// The whitespace-delimited token with text which is not a number with a unit, such as
+    /// "1.5h", or "5" in "5 s". This is an empty string when the string is empty.
+    Invalid(&'a str),
+}
+
+impl std::fmt::Display for SyntaxError<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.format())
+        match self {
+            SyntaxError::UnknownUnit(unit) => write!(f, "unknown unit {:?}", unit),
+            SyntaxError::Invalid(token) => {
+                write!(f, "expected numbers with units, but found {:?}", token)
+            }
+        }
+    }
+}
+
+/// Check that `s` is a bare number, or only numbers with standard units, for parsing strictly
+/// rather than ignoring what [FancyDuration::parse] does not understand.
+pub(crate) fn check_syntax(s: &str) -> Result<(), SyntaxError<'_>> {
+    let (_, s) = split_sign(s);
+
+    if parse_number(s, 1).is_some() {
+        return Ok(());
+    }
+
+    // the error for the text from `start` to `end`, which is not a number with a unit.
+    let invalid = |start: usize, end: usize| {
+        let skipped = &s[start..end];
+        let start = start + (skipped.len() - skipped.trim_start().len());
+        let from = s[..start]
+            .trim_end_matches(|c: char| !c.is_whitespace())
+            .len();
+        let to = s[start..]
+            .find(char::is_whitespace)
+            .map_or(s.len(), |i| start + i);
+
+        if skipped.trim().bytes().all(|b| b.is_ascii_alphabetic()) {
+            SyntaxError::UnknownUnit(skipped.trim())
+        } else {
+            SyntaxError::Invalid(&s[from..to])
+        }
+    };
+
+    let mut end = 0;
+
+    for captures in units::UNIT_FORMAT.captures_iter(s) {
+        let matched = captures.get(0).unwrap();
+        let skipped = &s[end..matched.start()];
+
+        if !skipped.trim().is_empty() {
+            return Err(invalid(end, matched.start()));
+        }
+
+        let suffix = captures.get(2).unwrap().as_str();
+
+        if !DURATION_PARTS.iter().any(|part| part.suffix() == suffix) {
+            return Err(SyntaxError::UnknownUnit(suffix));
+        }
+
+        end = matched.end();
+    }
+
+    if !s[end..].trim().is_empty() {
+        return Err(invalid(end, s.len()));
+    }
+
+    if end == 0 {
+        return Err(SyntaxError::Invalid(""));
+    }
+
+    Ok(())
+}
+
+/// Split a count of nanoseconds into (seconds, nanoseconds).
+fn split_ns(total: u128) -> Result<(u64, u64), anyhow::Error> {
+    Ok((
+        (total / 1e9 as u128).try_into()?,
+        (total % 1e9 as u128) as u64,
+    ))
+}
+
+/// Strip the trailing zeroes from `n`, the digits of a fraction zero-padded to `width`, yielding
+/// the remaining digits and their width. For example, (500, 9) is (5, 1) for ".5".
+pub(crate) fn trim_fraction(mut n: u64, mut width: usize) -> (u64, usize) {
+    while n != 0 && n.is_multiple_of(10) {
+        n /= 10;
+        width -= 1;
+    }
+
+    (n, width)
+}
+
+/// Split a leading "-" from a fancy duration, for signed durations.
+pub(crate) fn split_sign(s: &str) -> (bool, &str) {
+    match s.trim_start().strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s),
+    }
+}
+
+/// Format a value to `precision` significant digits, removing any trailing zeroes.
+fn format_significant(value: f64, precision: usize) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+
+    let magnitude = value.abs().log10().floor() as isize;
+    let decimals = (precision.max(1) as isize - 1 - magnitude).max(0) as usize;
+    let s = format!("{:.*}", decimals, value);
+
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        s
+    }
+}
+
+/// Write `s` to the formatter, honoring its width, fill and alignment. Unlike
+/// [std::fmt::Formatter::pad], alignment defaults to the right, and precision is not used to
+/// truncate the string.
+fn pad_aligned(f: &mut std::fmt::Formatter<'_>, s: &str) -> std::fmt::Result {
+    use std::fmt::Alignment;
+
+    let len = s.chars().count();
+    let padding = match f.width() {
+        Some(width) if width > len => width - len,
+        _ => return f.write_str(s),
+    };
+
+    let (pre, post) = match f.align() {
+        Some(Alignment::Left) => (0, padding),
+        Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+        Some(Alignment::Right) | None => (padding, 0),
+    };
+
+    let fill = f.fill();
+
+    for _ in 0..pre {
+        f.write_char(fill)?;
+    }
+
+    f.write_str(s)?;
+
+    for _ in 0..post {
+        f.write_char(fill)?;
     }
+
+    Ok(())
 }
 
+/// Serializes the standard representation, e.g. "1h 30m", in human-readable formats such as
+/// JSON. Binary formats such as bincode instead get a tuple of (seconds, nanoseconds, negative),
+/// which is the length of the duration, or of (parts, negative) for durations which keep their
+/// parts, see [AsTimes::as_parts], where the parts are serialized as [DurationBreakdown] is.
 #[cfg(feature = "serde")]
 impl<D> Serialize for FancyDuration<D>
 where
@@ -649,145 +1711,919 @@ where
 {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
-        S: serde::Serializer,
+        S: ::serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_string())
+        } else if let Some(parts) = self.0.as_parts() {
+            (DurationBreakdown::from_parts(&parts), self.0.is_negative()).serialize(serializer)
+        } else {
+            let (s, ns) = self.0.as_times();
+            (s, ns as u32, self.0.is_negative()).serialize(serializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for DurationPart {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.serialize_str(&format!("{}s", self.name()))
+    }
+}
+
+#[cfg(feature = "serde")]
+struct DurationPartVisitor;
+
+#[cfg(feature = "serde")]
+impl Visitor<'_> for DurationPartVisitor {
+    type Value = DurationPart;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("expecting the name or suffix of a duration part, e.g. \"hours\"")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        DurationPart::from_key(v).ok_or_else(|| {
+            ::serde::de::Error::unknown_variant(
+                v,
+                &[
+                    "years",
+                    "months",
+                    "weeks",
+                    "days",
+                    "hours",
+                    "minutes",
+                    "seconds",
+                    "milliseconds",
+                    "microseconds",
+                    "nanoseconds",
+                ],
+            )
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for DurationPart {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(DurationPartVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for DurationBreakdown {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.collect_map(self.parts())
+    }
+}
+
+#[cfg(feature = "serde")]
+struct DurationBreakdownVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> Visitor<'de> for DurationBreakdownVisitor {
+    type Value = DurationBreakdown;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("expecting a map of duration parts to counts, e.g. {\"hours\": 1}")
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+    where
+        A: ::serde::de::MapAccess<'de>,
+    {
+        let mut obj = DurationBreakdown::default();
+        let mut seen = Vec::new();
+
+        while let Some((part, value)) = access.next_entry::<DurationPart, u64>()? {
+            if seen.contains(&part) {
+                return Err(::serde::de::Error::custom(format!(
+                    "duplicate field `{}s`",
+                    part.name()
+                )));
+            }
+
+            *obj.get_mut(&part) = value;
+            seen.push(part);
+        }
+
+        Ok(obj)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for DurationBreakdown {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(DurationBreakdownVisitor)
+    }
+}
+
+/// Deserializes a [FancyDuration] from the fancy format, ISO 8601, or a number of `unit`.
+#[cfg(feature = "serde")]
+struct FancyDurationVisitor<D: AsTimes> {
+    unit: DurationPart,
+    marker: PhantomData<D>,
+}
+
+#[cfg(feature = "serde")]
+impl<D> FancyDurationVisitor<D>
+where
+    D: AsTimes + Clone,
+{
+    fn duration_from_ns<E>(&self, negative: bool, total: u128) -> Result<FancyDuration<D>, E>
+    where
+        E: ::serde::de::Error,
+    {
+        split_ns(total)
+            .and_then(|times| from_parsed(negative, times))
+            .map(FancyDuration::new)
+            .map_err(::serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, D> Visitor<'de> for FancyDurationVisitor<D>
+where
+    D: AsTimes + Clone,
+{
+    type Value = FancyDuration<D>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            formatter,
+            "expecting a duration in 'fancy' format, in ISO 8601 format, as a number of {}s or as a \
+             map of duration parts",
+            self.unit.name()
+        )
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        let (negative, number) = split_sign(v);
+
+        if let Some(total) = parse_number(number, self.unit.as_ns()) {
+            return self.duration_from_ns(negative, total.map_err(::serde::de::Error::custom)?);
+        }
+
+        FancyDuration::parse_strict(v).map_err(::serde::de::Error::custom)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        self.duration_from_ns(false, v as u128 * self.unit.as_ns())
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        self.duration_from_ns(v < 0, v.unsigned_abs() as u128 * self.unit.as_ns())
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        let ns = (v.abs() * self.unit.as_ns() as f64).round();
+
+        if !ns.is_finite() || ns >= u64::MAX as f64 * 1e9 {
+            return Err(::serde::de::Error::custom(format!(
+                "{} {}s is out of range",
+                v,
+                self.unit.name()
+            )));
+        }
+
+        self.duration_from_ns(v.is_sign_negative() && ns > 0.0, ns as u128)
+    }
+
+    fn visit_map<A>(self, access: A) -> Result<Self::Value, A::Error>
+    where
+        A: ::serde::de::MapAccess<'de>,
+    {
+        let breakdown =
+            DurationBreakdown::deserialize(::serde::de::value::MapAccessDeserializer::new(access))?;
+
+        D::parse_to_duration("0")
+            .and_then(|zero| zero.try_from_parts(false, &breakdown.parts()))
+            .map(FancyDuration::new)
+            .map_err(::serde::de::Error::custom)
+    }
+}
+
+/// Deserialize a [FancyDuration], reading numbers in `unit`.
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_in<'de, T, D>(
+    deserializer: D,
+    unit: DurationPart,
+) -> Result<FancyDuration<T>, D::Error>
+where
+    T: AsTimes + Clone,
+    D: ::serde::Deserializer<'de>,
+{
+    if !deserializer.is_human_readable() {
+        let zero = T::parse_to_duration("0").map_err(::serde::de::Error::custom)?;
+
+        if zero.as_parts().is_some() {
+            let (breakdown, negative) = <(DurationBreakdown, bool)>::deserialize(deserializer)?;
+
+            return zero
+                .try_from_parts(negative, &breakdown.parts())
+                .map(FancyDuration::new)
+                .map_err(::serde::de::Error::custom);
+        }
+
+        let (s, ns, negative) = <(u64, u32, bool)>::deserialize(deserializer)?;
+
+        if ns >= 1e9 as u32 {
+            return Err(::serde::de::Error::custom(format!(
+                "{} nanoseconds is not less than a second",
+                ns
+            )));
+        }
+
+        return from_parsed(negative, (s, ns as u64))
+            .map(FancyDuration::new)
+            .map_err(::serde::de::Error::custom);
+    }
+
+    deserializer.deserialize_any(FancyDurationVisitor {
+        unit,
+        marker: PhantomData,
+    })
+}
+
+/// Deserializes from the fancy format, ISO 8601, a number of seconds, or a map of parts to
+/// counts such as `{"h":1,"m":30}`, see [DurationBreakdown]. Numbers may be in a string, e.g.
+/// "30", and other units for numbers are available with the adapters in [crate::serde]. Binary
+/// formats read the tuple written by [Serialize].
+#[cfg(feature = "serde")]
+impl<'de, T> Deserialize<'de> for FancyDuration<T>
+where
+    T: AsTimes + Clone,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
     {
-        serializer.serialize_str(&self.to_string())
+        deserialize_in(deserializer, DurationPart::Seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::FancyDuration;
+
+    #[test]
+    fn test_fancy_duration_call() {
+        use super::{AsFancyDuration, ParseFancyDuration};
+
+        assert_eq!(Duration::new(0, 600).fancy_duration().to_string(), "600ns");
+        #[cfg(feature = "time")]
+        assert_eq!(
+            time::Duration::new(0, 600).fancy_duration().to_string(),
+            "600ns"
+        );
+        #[cfg(feature = "chrono")]
+        assert_eq!(
+            chrono::Duration::nanoseconds(600)
+                .fancy_duration()
+                .to_string(),
+            "600ns"
+        );
+        assert_eq!(
+            Duration::parse_fancy_duration("600ns".to_string()).unwrap(),
+            Duration::new(0, 600)
+        );
+        #[cfg(feature = "time")]
+        assert_eq!(
+            time::Duration::parse_fancy_duration("600ns".to_string()).unwrap(),
+            time::Duration::new(0, 600)
+        );
+        #[cfg(feature = "chrono")]
+        assert_eq!(
+            chrono::Duration::parse_fancy_duration("600ns".to_string()).unwrap(),
+            chrono::Duration::nanoseconds(600)
+        );
+    }
+
+    #[test]
+    fn test_duration_to_string() {
+        assert_eq!(FancyDuration(Duration::new(0, 600)).to_string(), "600ns");
+        assert_eq!(FancyDuration(Duration::new(0, 600000)).to_string(), "600us");
+        assert_eq!(
+            FancyDuration(Duration::new(0, 600000000)).to_string(),
+            "600ms"
+        );
+        assert_eq!(FancyDuration(Duration::new(600, 0)).to_string(), "10m");
+        assert_eq!(FancyDuration(Duration::new(120, 0)).to_string(), "2m");
+        assert_eq!(FancyDuration(Duration::new(185, 0)).to_string(), "3m 5s");
+        assert_eq!(
+            FancyDuration(Duration::new(24 * 60 * 60, 0)).to_string(),
+            "1d"
+        );
+        assert_eq!(FancyDuration(Duration::new(324, 0)).to_string(), "5m 24s");
+        assert_eq!(
+            FancyDuration(Duration::new(24 * 60 * 60 + 324, 0)).to_string(),
+            "1d 5m 24s"
+        );
+        assert_eq!(
+            FancyDuration(Duration::new(27 * 24 * 60 * 60 + 324, 0)).to_string(),
+            "3w 6d 5m 24s"
+        );
+        assert_eq!(
+            FancyDuration(Duration::new(99 * 24 * 60 * 60 + 324, 0)).to_string(),
+            "3m 1w 2d 5m 24s"
+        );
+
+        assert_eq!(
+            FancyDuration(Duration::new(12 * 30 * 24 * 60 * 60 + 10 * 24 * 60 * 60, 0)).to_string(),
+            "1y 1w 3d"
+        );
+
+        assert_eq!(
+            FancyDuration(Duration::new(12 * 30 * 24 * 60 * 60 + 10 * 24 * 60 * 60, 0))
+                .format_compact(),
+            "1y1w3d"
+        );
+
+        assert_eq!(
+            FancyDuration(Duration::new(324, 0)).format_compact(),
+            "5m24s"
+        );
+        assert_eq!(
+            FancyDuration(Duration::new(24 * 60 * 60 + 324, 0)).format_compact(),
+            "1d5m24s"
+        );
+        assert_eq!(
+            FancyDuration(Duration::new(27 * 24 * 60 * 60 + 324, 0)).format_compact(),
+            "3w6d5m24s"
+        );
+        assert_eq!(
+            FancyDuration(Duration::new(99 * 24 * 60 * 60 + 324, 0)).format_compact(),
+            "3m1w2d5m24s"
+        );
+    }
+
+    #[test]
+    fn test_format_fixed() {
+        use super::DurationPart;
+
+        let hms = [
+            DurationPart::Hours,
+            DurationPart::Minutes,
+            DurationPart::Seconds,
+        ];
+
+        let duration_table = [
+            (Duration::new(0, 0), "00h 00m 00s"),
+            (Duration::new(185, 0), "00h 03m 05s"),
+            (Duration::new(3723, 0), "01h 02m 03s"),
+            (Duration::new(26 * 60 * 60, 500), "26h 00m 00s"),
+            (Duration::new(120 * 60 * 60, 0), "120h 00m 00s"),
+        ];
+
+        for (duration, formatted) in duration_table {
+            assert_eq!(FancyDuration(duration).format_fixed(&hms), formatted);
+        }
+
+        assert_eq!(
+            FancyDuration(Duration::new(3, 5000000))
+                .format_fixed(&[DurationPart::Milliseconds, DurationPart::Seconds]),
+            "03s 005ms"
+        );
+
+        assert_eq!(
+            format!("{:>12}|", FancyDuration(Duration::new(185, 0))),
+            "       3m 5s|"
+        );
+        assert_eq!(
+            format!("{:12}|", FancyDuration(Duration::new(3723, 0))),
+            "    1h 2m 3s|"
+        );
+        assert_eq!(
+            format!("{:-<12}|", FancyDuration(Duration::new(185, 0))),
+            "3m 5s-------|"
+        );
+        assert_eq!(
+            format!("{:^9}|", FancyDuration(Duration::new(185, 0))),
+            "  3m 5s  |"
+        );
+        assert_eq!(
+            format!("{:2}|", FancyDuration(Duration::new(185, 0))),
+            "3m 5s|"
+        );
+    }
+
+    #[test]
+    fn test_display_flags() {
+        let duration = FancyDuration(Duration::new(3723, 5000));
+
+        let format_table = [
+            (format!("{}", duration), "1h 2m 3s 5us"),
+            (format!("{:#}", duration), "1h2m3s5us"),
+            (format!("{:.2}", duration), "1h 2m"),
+            (format!("{:#.3}", duration), "1h2m3s"),
+            (format!("{:>8.2}", duration), "   1h 2m"),
+            (format!("{:*<8.1}", duration), "1h******"),
+            (format!("{:#12.2}", duration), "        1h2m"),
+            (format!("{:.0}", duration), "0"),
+        ];
+
+        for (formatted, expected) in format_table {
+            assert_eq!(formatted, expected);
+        }
+    }
+
+    #[test]
+    fn test_write_to() {
+        let duration_table = [
+            (Duration::new(0, 0), "0", "0"),
+            (Duration::new(185, 0), "3m 5s", "3m5s"),
+            (Duration::new(3723, 5000), "1h 2m 3s 5us", "1h2m3s5us"),
+            (
+                Duration::new(u64::MAX, 999999999),
+                "593066617596y 1m 1w 4d 7h 15s 999ms 999us 999ns",
+                "593066617596y1m1w4d7h15s999ms999us999ns",
+            ),
+        ];
+
+        for (duration, standard, compact) in duration_table {
+            let fancy = FancyDuration(duration);
+
+            let mut s = String::new();
+            fancy.write_to(&mut s).unwrap();
+            assert_eq!(s, standard);
+
+            let mut s = String::new();
+            fancy.write_compact_to(&mut s).unwrap();
+            assert_eq!(s, compact);
+
+            assert_eq!(fancy.format_to_array().as_str(), standard);
+            assert_eq!(fancy.format(), standard);
+            assert_eq!(fancy.format_compact(), compact);
+        }
+
+        // the longest possible representation, with every part at its maximum.
+        #[derive(Clone)]
+        struct Longest;
+
+        impl super::AsTimes for Longest {
+            fn as_times(&self) -> (u64, u64) {
+                (u64::MAX, 999999999)
+            }
+
+            fn parse_to_duration(_: &str) -> Result<Self, anyhow::Error> {
+                Ok(Longest)
+            }
+
+            fn from_times(&self, _: u64, _: u64) -> Self {
+                Longest
+            }
+
+            fn is_negative(&self) -> bool {
+                true
+            }
+
+            fn as_parts(&self) -> Option<Vec<(super::DurationPart, u64)>> {
+                Some(
+                    super::DURATION_PARTS
+                        .iter()
+                        .map(|part| (part.clone(), u64::MAX))
+                        .collect(),
+                )
+            }
+        }
+
+        let fancy = FancyDuration(Longest);
+        assert_eq!(fancy.format_to_array().len(), super::FORMAT_CAPACITY);
+        assert_eq!(fancy.format_to_array().as_str(), fancy.format());
+        assert_eq!(
+            format!("{:>240}", fancy),
+            " ".repeat(240 - super::FORMAT_CAPACITY) + &fancy.format()
+        );
     }
-}
 
-#[cfg(feature = "serde")]
-struct FancyDurationVisitor<D: AsTimes>(PhantomData<D>);
+    #[test]
+    fn test_format_with() {
+        use super::{DurationFormat, DurationPart};
 
-#[cfg(feature = "serde")]
-impl<D> Visitor<'_> for FancyDurationVisitor<D>
-where
-    D: AsTimes + Clone,
-{
-    type Value = FancyDuration<D>;
+        let format_table = [
+            (
+                Duration::new(49 * 60 * 60, 0),
+                DurationFormat::new(),
+                "2d 1h",
+            ),
+            (
+                Duration::new(49 * 60 * 60, 0),
+                DurationFormat::new().max_unit(DurationPart::Hours),
+                "49h",
+            ),
+            (
+                Duration::new(49 * 60 * 60 + 61, 0),
+                DurationFormat::new()
+                    .max_unit(DurationPart::Hours)
+                    .compact(true),
+                "49h1m1s",
+            ),
+            (
+                Duration::new(3723, 4005000),
+                DurationFormat::new().max_unit(DurationPart::Milliseconds),
+                "3723004ms 5us",
+            ),
+            (
+                Duration::new(3723, 4005000),
+                DurationFormat::new()
+                    .max_unit(DurationPart::Milliseconds)
+                    .min_unit(DurationPart::Milliseconds),
+                "3723004ms",
+            ),
+            (
+                Duration::new(99 * 24 * 60 * 60 + 3723, 0),
+                DurationFormat::new().min_unit(DurationPart::Minutes),
+                "3m 1w 2d 1h 2m",
+            ),
+            (
+                Duration::new(99 * 24 * 60 * 60 + 3723, 0),
+                DurationFormat::new()
+                    .max_unit(DurationPart::Days)
+                    .min_unit(DurationPart::Hours),
+                "99d 1h",
+            ),
+            (
+                Duration::new(30, 0),
+                DurationFormat::new().min_unit(DurationPart::Minutes),
+                "0",
+            ),
+        ];
 
-    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        formatter.write_str("expecting a duration in 'fancy' format")
-    }
+        for (duration, format, formatted) in format_table {
+            assert_eq!(FancyDuration(duration).format_with(&format), formatted);
+        }
 
-    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
-    where
-        E: serde::de::Error,
-    {
-        match FancyDuration::parse(v) {
-            Ok(res) => Ok(res),
-            Err(e) => Err(serde::de::Error::custom(e)),
+        // durations too long for the type, or negative for unsigned types, are an error, as they
+        // are with parse.
+        for s in [
+            "18446744073709551615m",
+            "18446744073709551615ms",
+            "18446744073709551615s 1s",
+            "-30s",
+            " - 1h",
+            "-30",
+            " - 1.5",
+        ] {
+            assert!(FancyDuration::<Duration>::parse(s).is_err(), "{}", s);
+            assert!(
+                FancyDuration::<Duration>::parse_with(s, &DurationFormat::new()).is_err(),
+                "{}",
+                s
+            );
         }
-    }
-}
 
-#[cfg(feature = "serde")]
-impl<'de, T> Deserialize<'de> for FancyDuration<T>
-where
-    T: AsTimes + Clone,
-{
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        deserializer.deserialize_str(FancyDurationVisitor(PhantomData::default()))
+        #[cfg(feature = "time")]
+        for s in ["18446744073709551615", "18446744073709551615s"] {
+            assert!(FancyDuration::<time::Duration>::parse(s).is_err(), "{}", s);
+            assert!(
+                FancyDuration::<time::Duration>::parse_with(s, &DurationFormat::new()).is_err(),
+                "{}",
+                s
+            );
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::time::Duration;
+    #[test]
+    fn test_calendar() {
+        use super::{Calendar, CalendarModel, DurationFormat};
 
-    use crate::FancyDuration;
+        const DAY: u64 = 24 * 60 * 60;
 
-    #[test]
-    fn test_fancy_duration_call() {
-        use super::{AsFancyDuration, ParseFancyDuration};
+        let calendar_table = [
+            (CalendarModel::Days360, 360 * DAY, 30 * DAY),
+            (CalendarModel::Days365, 365 * DAY, 2628000),
+            (CalendarModel::Julian, 31557600, 2629800),
+            (CalendarModel::Gregorian, 31556952, 2629746),
+        ];
 
-        assert_eq!(Duration::new(0, 600).fancy_duration().to_string(), "600ns");
-        #[cfg(feature = "time")]
-        assert_eq!(
-            time::Duration::new(0, 600).fancy_duration().to_string(),
-            "600ns"
-        );
-        #[cfg(feature = "chrono")]
-        assert_eq!(
-            chrono::Duration::nanoseconds(600)
-                .fancy_duration()
-                .to_string(),
-            "600ns"
-        );
+        for (model, year, month) in calendar_table {
+            let format = DurationFormat::new().calendar(model);
+
+            assert_eq!(
+                FancyDuration::<Duration>::parse_with("1y", &format)
+                    .unwrap()
+                    .duration(),
+                Duration::new(year, 0)
+            );
+            assert_eq!(
+                FancyDuration::<Duration>::parse_with("2m 1m", &format)
+                    .unwrap()
+                    .duration(),
+                Duration::new(2 * month + 60, 0)
+            );
+            assert_eq!(
+                FancyDuration(Duration::new(year + month + DAY, 0)).format_with(&format),
+                "1y 1m 1d"
+            );
+        }
+
+        let format = DurationFormat::new()
+            .calendar(Calendar::new(CalendarModel::Days365).with_month_seconds(31 * DAY));
         assert_eq!(
-            Duration::parse_fancy_duration("600ns".to_string()).unwrap(),
-            Duration::new(0, 600)
+            FancyDuration(Duration::new(365 * DAY + 32 * DAY, 0)).format_with(&format),
+            "1y 1m 1d"
         );
-        #[cfg(feature = "time")]
         assert_eq!(
-            time::Duration::parse_fancy_duration("600ns".to_string()).unwrap(),
-            time::Duration::new(0, 600)
+            FancyDuration::<Duration>::parse_with("1y 1m 1d", &format)
+                .unwrap()
+                .duration(),
+            Duration::new(365 * DAY + 32 * DAY, 0)
         );
-        #[cfg(feature = "chrono")]
+
+        // the default calendar has 360 day years.
         assert_eq!(
-            chrono::Duration::parse_fancy_duration("600ns".to_string()).unwrap(),
-            chrono::Duration::nanoseconds(600)
+            FancyDuration::<Duration>::parse("1y").unwrap().duration(),
+            Duration::new(360 * DAY, 0)
         );
     }
 
     #[test]
-    fn test_duration_to_string() {
-        assert_eq!(FancyDuration(Duration::new(0, 600)).to_string(), "600ns");
-        assert_eq!(FancyDuration(Duration::new(0, 600000)).to_string(), "600us");
-        assert_eq!(
-            FancyDuration(Duration::new(0, 600000000)).to_string(),
-            "600ms"
-        );
-        assert_eq!(FancyDuration(Duration::new(600, 0)).to_string(), "10m");
-        assert_eq!(FancyDuration(Duration::new(120, 0)).to_string(), "2m");
-        assert_eq!(FancyDuration(Duration::new(185, 0)).to_string(), "3m 5s");
-        assert_eq!(
-            FancyDuration(Duration::new(24 * 60 * 60, 0)).to_string(),
-            "1d"
-        );
-        assert_eq!(FancyDuration(Duration::new(324, 0)).to_string(), "5m 24s");
-        assert_eq!(
-            FancyDuration(Duration::new(24 * 60 * 60 + 324, 0)).to_string(),
-            "1d 5m 24s"
-        );
+    fn test_business_calendar() {
+        use super::{Calendar, DurationFormat};
+
+        const HOUR: u64 = 60 * 60;
+
+        let format = DurationFormat::new().calendar(Calendar::business(8, 5));
+
+        let business_table = [
+            ("1w 2d 3h", 59 * HOUR),
+            ("1d", 8 * HOUR),
+            ("2w", 80 * HOUR),
+            ("4d 7h 30m", 39 * HOUR + 30 * 60),
+            ("1y", 52 * 40 * HOUR),
+        ];
+
+        for (s, seconds) in business_table {
+            let duration = FancyDuration::<Duration>::parse_with(s, &format).unwrap();
+            assert_eq!(duration.duration(), Duration::new(seconds, 0), "{}", s);
+            assert_eq!(duration.format_with(&format), s);
+        }
+
         assert_eq!(
-            FancyDuration(Duration::new(27 * 24 * 60 * 60 + 324, 0)).to_string(),
-            "3w 6d 5m 24s"
+            FancyDuration(Duration::new(9 * HOUR, 0)).format_with(&format),
+            "1d 1h"
         );
+
+        let calendar = Calendar::default()
+            .with_day_seconds(10 * HOUR)
+            .with_week_seconds(40 * HOUR);
+        assert_eq!(calendar.day_seconds(), 10 * HOUR);
+        assert_eq!(calendar.week_seconds(), 40 * HOUR);
         assert_eq!(
-            FancyDuration(Duration::new(99 * 24 * 60 * 60 + 324, 0)).to_string(),
-            "3m 1w 2d 5m 24s"
+            FancyDuration(Duration::new(51 * HOUR, 0))
+                .format_with(&DurationFormat::new().calendar(calendar)),
+            "1w 1d 1h"
         );
+    }
+
+    #[test]
+    fn test_aliases() {
+        use super::{DurationFormat, DurationPart, Unit};
+
+        const HOUR: u64 = 60 * 60;
+
+        let format = DurationFormat::new()
+            .alias("fortnight", Duration::new(14 * 24 * HOUR, 0))
+            .alias("sprint", Duration::new(14 * 24 * HOUR, 0))
+            .alias("shift", Duration::new(8 * HOUR, 0))
+            .alias("tick", Duration::from_millis(50));
+
+        let alias_table = [
+            (
+                "1sprint",
+                Duration::new(14 * 24 * HOUR, 0),
+                "2w",
+                "1fortnight",
+            ),
+            ("2shift 1h", Duration::new(17 * HOUR, 0), "17h", "2shift 1h"),
+            ("3tick", Duration::from_millis(150), "150ms", "3tick"),
+            (
+                "1fortnight 1d 1shift 1m 1tick",
+                Duration::new(15 * 24 * HOUR + 8 * HOUR + 60, 50000000),
+                "2w 1d 8h 1m 50ms",
+                "1fortnight 1d 1shift 1m 1tick",
+            ),
+            ("1m 0d", Duration::new(30 * 24 * HOUR, 0), "1m", "1m"),
+        ];
+
+        for (s, duration, formatted, preferred) in alias_table {
+            let parsed = FancyDuration::<Duration>::parse_with(s, &format).unwrap();
+            assert_eq!(parsed.duration(), duration, "{}", s);
+            assert_eq!(parsed.format_with(&format), formatted, "{}", s);
+            assert_eq!(
+                parsed.format_with(&format.clone().prefer_aliases(true)),
+                preferred,
+                "{}",
+                s
+            );
+        }
 
+        // aliases do not change the standard parser.
         assert_eq!(
-            FancyDuration(Duration::new(12 * 30 * 24 * 60 * 60 + 10 * 24 * 60 * 60, 0)).to_string(),
-            "1y 1w 3d"
+            FancyDuration::<Duration>::parse("1sprint 1h")
+                .unwrap()
+                .duration(),
+            Duration::new(HOUR, 0)
         );
 
+        let format = format
+            .prefer_aliases(true)
+            .max_unit(DurationPart::Days)
+            .min_unit(DurationPart::Hours);
         assert_eq!(
-            FancyDuration(Duration::new(12 * 30 * 24 * 60 * 60 + 10 * 24 * 60 * 60, 0))
-                .format_compact(),
-            "1y1w3d"
+            FancyDuration(Duration::new(15 * 24 * HOUR + 9 * HOUR + 1, 0)).format_with(&format),
+            "15d 1shift 1h"
         );
 
+        let format = DurationFormat::new()
+            .unit_system(&vec![
+                Unit::new("day", &["d"], 24 * HOUR as u128 * 1_000_000_000),
+                Unit::new("hour", &["h"], HOUR as u128 * 1_000_000_000),
+            ])
+            .alias("shift", Duration::new(8 * HOUR, 0))
+            .prefer_aliases(true);
         assert_eq!(
-            FancyDuration(Duration::new(324, 0)).format_compact(),
-            "5m24s"
+            FancyDuration::<Duration>::parse_with("1d 1shift 1h", &format)
+                .unwrap()
+                .format_with(&format),
+            "1d 1shift 1h"
         );
+
+        // a count of a long unit may overflow even nanoseconds in a u128.
+        let format = DurationFormat::new().unit_system(&vec![Unit::new(
+            "millennium",
+            &["mil"],
+            1000 * 365 * 24 * HOUR as u128 * 1_000_000_000,
+        )]);
         assert_eq!(
-            FancyDuration(Duration::new(24 * 60 * 60 + 324, 0)).format_compact(),
-            "1d5m24s"
+            FancyDuration::<Duration>::parse_with("2mil", &format)
+                .unwrap()
+                .duration(),
+            Duration::new(2000 * 365 * 24 * HOUR, 0)
         );
         assert_eq!(
-            FancyDuration(Duration::new(27 * 24 * 60 * 60 + 324, 0)).format_compact(),
-            "3w6d5m24s"
+            FancyDuration::<Duration>::parse_with("18446744073709551615mil", &format)
+                .unwrap_err()
+                .to_string(),
+            "duration is out of range"
         );
+    }
+
+    #[test]
+    fn test_default_unit() {
+        use super::{Calendar, DurationFormat, DurationPart};
+
+        let parse_table = [
+            ("30", Duration::new(30, 0)),
+            (" 1.5 ", Duration::new(1, 500000000)),
+            ("0.000000001", Duration::new(0, 1)),
+            ("0", Duration::ZERO),
+        ];
+
+        for (s, duration) in parse_table {
+            assert_eq!(
+                FancyDuration::<Duration>::parse(s).unwrap().duration(),
+                duration,
+                "{}",
+                s
+            );
+            assert_eq!(
+                FancyDuration::<Duration>::parse_with(s, &DurationFormat::new())
+                    .unwrap()
+                    .duration(),
+                duration,
+                "{}",
+                s
+            );
+        }
+
+        let default_unit_table = [
+            (
+                DurationPart::Milliseconds,
+                "250",
+                Duration::new(0, 250000000),
+            ),
+            (DurationPart::Milliseconds, "1.5", Duration::new(0, 1500000)),
+            (DurationPart::Hours, "2", Duration::new(7200, 0)),
+            (DurationPart::Days, "0.5", Duration::new(12 * 3600, 0)),
+            // units still apply when given.
+            (DurationPart::Milliseconds, "250s", Duration::new(250, 0)),
+        ];
+
+        for (unit, s, duration) in default_unit_table {
+            assert_eq!(
+                FancyDuration::<Duration>::parse_with(s, &DurationFormat::new().default_unit(unit))
+                    .unwrap()
+                    .duration(),
+                duration,
+                "{}",
+                s
+            );
+        }
+
         assert_eq!(
-            FancyDuration(Duration::new(99 * 24 * 60 * 60 + 324, 0)).format_compact(),
-            "3m1w2d5m24s"
+            FancyDuration::<Duration>::parse_with(
+                "1",
+                &DurationFormat::new()
+                    .calendar(Calendar::business(8, 5))
+                    .default_unit(DurationPart::Days)
+            )
+            .unwrap()
+            .duration(),
+            Duration::new(8 * 3600, 0)
         );
+
+        // these are not bare numbers, so they are ignored as any text without a unit is.
+        for s in ["1.", ".5", "1.5.5"] {
+            assert_eq!(
+                FancyDuration::<Duration>::parse(s).unwrap().duration(),
+                Duration::ZERO,
+                "{}",
+                s
+            );
+        }
+
+        assert!(FancyDuration::<Duration>::parse("99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn test_format_decimal() {
+        use super::DurationPart;
+
+        let duration_table = [
+            (Duration::new(0, 0), 3, "0"),
+            (Duration::new(0, 6), 5, "6ns"),
+            (Duration::new(0, 1234567), 3, "1.23ms"),
+            (Duration::new(0, 1920000), 3, "1.92ms"),
+            (Duration::new(0, 999999), 3, "1ms"),
+            (Duration::new(0, 999999999), 4, "1s"),
+            (Duration::new(90 * 60, 0), 3, "1.5h"),
+            (Duration::new(90 * 60, 0), 1, "2h"),
+            (Duration::new(185, 0), 5, "3.0833m"),
+            (Duration::new(10 * 24 * 60 * 60, 0), 2, "1.4w"),
+            (Duration::new(90, 0), 2, "1.5m"),
+            // months and years are left out, as "1.5m" is 90 seconds.
+            (Duration::new(45 * 24 * 60 * 60, 0), 2, "6.4w"),
+            (Duration::new(720 * 24 * 60 * 60, 0), 4, "102.9w"),
+        ];
+
+        for (duration, precision, formatted) in duration_table {
+            assert_eq!(FancyDuration(duration).format_decimal(precision), formatted);
+        }
+
+        let fixed_table = [
+            (Duration::new(15 * 60, 0), DurationPart::Hours, 2, "0.25h"),
+            (Duration::new(3, 0), DurationPart::Milliseconds, 2, "3000ms"),
+            (
+                Duration::new(0, 1500),
+                DurationPart::Milliseconds,
+                3,
+                "0.0015ms",
+            ),
+            (Duration::new(0, 0), DurationPart::Seconds, 3, "0s"),
+            (
+                Duration::new(45 * 24 * 60 * 60, 0),
+                DurationPart::Months,
+                2,
+                "1.5m",
+            ),
+        ];
+
+        for (duration, part, precision, formatted) in fixed_table {
+            assert_eq!(
+                FancyDuration(duration).format_decimal_in(part, precision),
+                formatted
+            );
+        }
     }
 
     #[test]
@@ -962,6 +2798,142 @@ mod tests {
     }
 
     #[test]
+    fn test_breakdown() {
+        use super::{DurationBreakdown, DurationPart};
+
+        let breakdown = FancyDuration(Duration::new(5403, 2000)).breakdown();
+        assert_eq!(
+            breakdown,
+            DurationBreakdown {
+                hours: 1,
+                minutes: 30,
+                seconds: 3,
+                microseconds: 2,
+                ..Default::default()
+            }
+        );
+        assert_eq!(breakdown.get(&DurationPart::Minutes), 30);
+        assert_eq!(breakdown.as_times(), (5403, 2000));
+        assert_eq!(DurationBreakdown::new(5403, 2000), breakdown);
+
+        let key_table = [
+            ("hours", Some(DurationPart::Hours)),
+            ("hour", Some(DurationPart::Hours)),
+            ("h", Some(DurationPart::Hours)),
+            ("m", Some(DurationPart::Minutes)),
+            ("months", Some(DurationPart::Months)),
+            ("ms", Some(DurationPart::Milliseconds)),
+            ("s", Some(DurationPart::Seconds)),
+            ("ns", Some(DurationPart::Nanoseconds)),
+            ("hourss", None),
+            ("fortnight", None),
+            ("", None),
+        ];
+
+        for (key, part) in key_table {
+            assert_eq!(DurationPart::from_key(key), part, "{}", key);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_breakdown_serde() {
+        use super::{DurationBreakdown, DurationPart};
+
+        let breakdown = FancyDuration(Duration::new(5400, 0)).breakdown();
+        let json = r#"{"hours":1,"minutes":30}"#;
+        assert_eq!(serde_json::to_string(&breakdown).unwrap(), json);
+        assert_eq!(
+            serde_json::from_str::<DurationBreakdown>(json).unwrap(),
+            breakdown
+        );
+        assert_eq!(
+            serde_json::to_string(&DurationBreakdown::default()).unwrap(),
+            "{}"
+        );
+
+        assert_eq!(
+            serde_json::to_string(&DurationPart::Milliseconds).unwrap(),
+            r#""milliseconds""#
+        );
+        assert_eq!(
+            serde_json::from_str::<DurationPart>(r#""d""#).unwrap(),
+            DurationPart::Days
+        );
+        assert!(serde_json::from_str::<DurationPart>(r#""fortnight""#).is_err());
+
+        let map_table = [
+            (r#"{"h":1,"m":30}"#, Duration::new(5400, 0)),
+            (r#"{"hours":1}"#, Duration::new(3600, 0)),
+            (r#"{"months":1,"day":1}"#, Duration::new(31 * 24 * 3600, 0)),
+            (r#"{"minutes":90,"ns":5}"#, Duration::new(5400, 5)),
+            (r#"{}"#, Duration::ZERO),
+        ];
+
+        for (json, duration) in map_table {
+            assert_eq!(
+                serde_json::from_str::<FancyDuration<Duration>>(json)
+                    .unwrap()
+                    .duration(),
+                duration,
+                "{}",
+                json
+            );
+        }
+
+        for json in [
+            r#"{"fortnights":1}"#,
+            r#"{"h":1,"hours":2}"#,
+            r#"{"h":-1}"#,
+            r#"{"h":"1"}"#,
+            r#"{"years":18446744073709551615}"#,
+            r#"{"seconds":18446744073709551615,"ms":1000}"#,
+        ] {
+            assert!(
+                serde_json::from_str::<FancyDuration<Duration>>(json).is_err(),
+                "{}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_syntax() {
+        use super::SyntaxError::{Invalid, UnknownUnit};
+
+        let syntax_table = [
+            ("1h 30m", Ok(())),
+            ("1h30m", Ok(())),
+            ("-5s", Ok(())),
+            ("30", Ok(())),
+            ("1.5", Ok(())),
+            ("1x", Err(UnknownUnit("x"))),
+            ("1h 2fortnights", Err(UnknownUnit("fortnights"))),
+            ("1H", Err(UnknownUnit("H"))),
+            ("abc", Err(UnknownUnit("abc"))),
+            ("1h and 30m", Err(UnknownUnit("and"))),
+            ("1h 30", Err(Invalid("30"))),
+            ("1h, 30m", Err(Invalid("1h,"))),
+            ("1h,30m", Err(Invalid("1h,30m"))),
+            ("1.5h", Err(Invalid("1.5h"))),
+            ("5 s", Err(Invalid("5"))),
+            ("1h 5 s", Err(Invalid("5"))),
+            ("--1s", Err(Invalid("-1s"))),
+            ("", Err(Invalid(""))),
+        ];
+
+        for (s, result) in syntax_table {
+            assert_eq!(super::check_syntax(s), result, "{}", s);
+        }
+
+        assert_eq!(
+            super::check_syntax("1.5h").unwrap_err().to_string(),
+            "expected numbers with units, but found \"1.5h\""
+        );
+    }
+
+    #[test]
+    #[allow(clippy::needless_borrow)]
     fn test_parse_filter() {
         use super::DurationPart;
         let duration_table = [
@@ -1216,6 +3188,71 @@ mod tests {
             assert_eq!(serde_json::to_string(&md).unwrap(), item.0);
         }
 
+        let number_table = [
+            ("{\"duration\":30}", Duration::new(30, 0)),
+            ("{\"duration\":1.25}", Duration::new(1, 250000000)),
+            ("{\"duration\":\"30\"}", Duration::new(30, 0)),
+        ];
+
+        for item in number_table {
+            let md: StdDuration = serde_json::from_str(item.0).unwrap();
+            assert_eq!(md.duration.duration(), item.1);
+        }
+
+        assert!(serde_json::from_str::<StdDuration>("{\"duration\":-1e300}").is_err());
+
+        // Duration cannot be negative, but negative zero is zero.
+        for json in [
+            "{\"duration\":-30}",
+            "{\"duration\":-1.5}",
+            "{\"duration\":\"-30\"}",
+            "{\"duration\":\"-30s\"}",
+            "{\"duration\":\"-PT30S\"}",
+        ] {
+            let err = serde_json::from_str::<StdDuration>(json).err().unwrap();
+            assert!(err.to_string().contains("cannot be negative"), "{}", json);
+        }
+
+        // strings which parse would partly ignore are an error.
+        for json in [
+            "{\"duration\":\"1x\"}",
+            "{\"duration\":\"1.5h\"}",
+            "{\"duration\":\"1 h\"}",
+            "{\"duration\":\"\"}",
+        ] {
+            assert!(
+                serde_json::from_str::<StdDuration>(json).is_err(),
+                "{}",
+                json
+            );
+        }
+
+        for json in ["{\"duration\":-0.0}", "{\"duration\":\"-0s\"}"] {
+            let md: StdDuration = serde_json::from_str(json).unwrap();
+            assert_eq!(md.duration.duration(), Duration::ZERO, "{}", json);
+        }
+
+        // binary formats get the length rather than a string.
+        for (_, duration) in duration_table {
+            let md = StdDuration {
+                duration: FancyDuration(duration),
+            };
+            let encoded = bincode::serialize(&md).unwrap();
+            assert_eq!(encoded.len(), 8 + 4 + 1);
+
+            let decoded: StdDuration = bincode::deserialize(&encoded).unwrap();
+            assert_eq!(decoded.duration.duration(), duration);
+        }
+
+        assert!(bincode::deserialize::<StdDuration>(
+            &bincode::serialize(&(1u64, 1e9 as u32, false)).unwrap()
+        )
+        .is_err());
+        assert!(bincode::deserialize::<StdDuration>(
+            &bincode::serialize(&(1u64, 0u32, true)).unwrap()
+        )
+        .is_err());
+
         #[cfg(feature = "time")]
         {
             #[derive(Serialize, Deserialize)]
@@ -1238,6 +3275,25 @@ mod tests {
                 assert_eq!(md.duration.duration(), item.1);
                 assert_eq!(serde_json::to_string(&md).unwrap(), item.0);
             }
+
+            for json in [
+                "18446744073709551615",
+                "\"18446744073709551615\"",
+                "1e19",
+                "{\"years\":18446744073709551615}",
+            ] {
+                assert!(
+                    serde_json::from_str::<FancyDuration<time::Duration>>(json).is_err(),
+                    "{}",
+                    json
+                );
+            }
+
+            // a corrupt or hostile length is an error rather than a panic.
+            assert!(bincode::deserialize::<FancyDuration<time::Duration>>(
+                &bincode::serialize(&(u64::MAX, 0u32, false)).unwrap()
+            )
+            .is_err());
         }
 
         #[cfg(feature = "chrono")]
@@ -1268,6 +3324,18 @@ mod tests {
                 assert_eq!(md.duration.duration(), item.1);
                 assert_eq!(serde_json::to_string(&md).unwrap(), item.0);
             }
+
+            for json in [
+                "18446744073709551615",
+                "1e16",
+                "{\"weeks\":18446744073709551615}",
+            ] {
+                assert!(
+                    serde_json::from_str::<FancyDuration<chrono::Duration>>(json).is_err(),
+                    "{}",
+                    json
+                );
+            }
         }
     }
 }
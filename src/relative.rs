@@ -0,0 +1,443 @@
+//! Relative phrasing of points in time, such as "in 5m" or "3 hours ago", and parsing of
+//! relative datetime expressions such as "now - 1d".
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+use crate::{parse_parts, CalendarArithmetic, CalendarDuration};
+use crate::{AsTimes, DurationBreakdown, FancyDuration};
+use std::time::{Duration, SystemTime};
+
+/// A [RelativeTime] describes a point in time relative to a reference "now", and formats as
+/// "in 5m", "5m ago" or "just now". Anything that converts into a [SystemTime] can be used,
+/// which includes `chrono::DateTime` and `time::OffsetDateTime`. "now" is always supplied by the
+/// caller so that output is predictable.
+///
+/// By default the offset is truncated to its most significant unit (see
+/// [FancyDuration::truncate]) and offsets below one second are "just now".
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use fancy_duration::RelativeTime;
+///
+/// let now = SystemTime::now();
+///
+/// assert_eq!(RelativeTime::new(now - Duration::new(330, 0), now).to_string(), "5m ago");
+/// assert_eq!(RelativeTime::new(now + Duration::new(330, 0), now).to_string(), "in 5m");
+/// assert_eq!(
+///     RelativeTime::new(now - Duration::new(330, 0), now).long(true).truncate(2).to_string(),
+///     "5 minutes 30 seconds ago"
+/// );
+/// assert_eq!(RelativeTime::new(now, now).to_string(), "just now");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct RelativeTime {
+    offset: FancyDuration<Duration>,
+    future: bool,
+    long: bool,
+    limit: usize,
+    just_now: Duration,
+}
+
+impl RelativeTime {
+    /// Construct a relative time describing `time` as seen from `now`.
+    pub fn new(time: impl Into<SystemTime>, now: impl Into<SystemTime>) -> Self {
+        let (offset, future) = match now.into().duration_since(time.into()) {
+            Ok(offset) => (offset, false),
+            Err(e) => (e.duration(), true),
+        };
+
+        Self::from_offset(offset, future)
+    }
+
+    /// Construct a relative time from the offset to "now", which is in the future if `future` is
+    /// set, and in the past otherwise.
+    pub fn from_offset(offset: Duration, future: bool) -> Self {
+        Self {
+            offset: FancyDuration::new(offset),
+            future,
+            long: false,
+            limit: 1,
+            just_now: Duration::new(1, 0),
+        }
+    }
+
+    /// Use the long form with unit names, e.g. "5 minutes ago" instead of "5m ago".
+    pub fn long(mut self, long: bool) -> Self {
+        self.long = long;
+        self
+    }
+
+    /// Truncate the offset to `limit` significant units, see [FancyDuration::truncate]. The
+    /// default is one.
+    pub fn truncate(mut self, limit: usize) -> Self {
+        self.limit = limit.max(1);
+        self
+    }
+
+    /// Offsets below this threshold are formatted as "just now". The default is one second.
+    pub fn just_now(mut self, threshold: Duration) -> Self {
+        self.just_now = threshold;
+        self
+    }
+
+    /// The offset between the time and "now".
+    pub fn offset(&self) -> Duration {
+        self.offset.duration()
+    }
+
+    /// Whether the time is in the future of "now".
+    pub fn is_future(&self) -> bool {
+        self.future
+    }
+}
+
+impl std::fmt::Display for RelativeTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.offset.0 < self.just_now {
+            return f.write_str("just now");
+        }
+
+        if self.future {
+            f.write_str("in ")?;
+        }
+
+        let offset = self.offset.truncate(self.limit);
+
+        if self.long {
+            let times = offset.0.as_times();
+            DurationBreakdown::new(times.0, times.1).write_long_to(f)?;
+        } else {
+            offset.write_to(f)?;
+        }
+
+        if !self.future {
+            f.write_str(" ago")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse relative datetime expressions, such as the ones accepted by `--since` and `--until`
+/// arguments, into a concrete datetime against a reference time supplied by the caller:
+///
+/// - `<duration> ago` and `in <duration>`, e.g. "24h ago" or "in 2h 30m".
+/// - An optional anchor, followed by an optional sign and duration, e.g. "-2h", "now-1d",
+///   "today", or "yesterday + 9h". The anchors are `now`, `today` (midnight of the reference
+///   time's day), `yesterday` and `tomorrow` (midnight of the day before and after). Without an
+///   anchor, the sign is required.
+///
+/// Keywords are case insensitive. Durations use the fancy duration syntax and are applied with
+/// [CalendarDuration], so "now - 1m 0d" is one calendar month ago.
+///
+/// ```
+/// # #[cfg(feature = "chrono")]
+/// # {
+/// use chrono::{TimeZone, Utc};
+/// use fancy_duration::ParseRelativeTime;
+///
+/// let now = Utc.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap();
+///
+/// assert_eq!(
+///     chrono::DateTime::parse_relative_time("now-1d", &now).unwrap(),
+///     Utc.with_ymd_and_hms(2024, 3, 14, 12, 0, 0).unwrap()
+/// );
+/// assert_eq!(
+///     chrono::DateTime::parse_relative_time("24h ago", &now).unwrap(),
+///     Utc.with_ymd_and_hms(2024, 3, 14, 12, 0, 0).unwrap()
+/// );
+/// assert_eq!(
+///     chrono::DateTime::parse_relative_time("yesterday + 9h", &now).unwrap(),
+///     Utc.with_ymd_and_hms(2024, 3, 14, 9, 0, 0).unwrap()
+/// );
+/// # }
+/// ```
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub trait ParseRelativeTime: Sized {
+    /// Parse a relative datetime expression, resolved against `reference`.
+    fn parse_relative_time(s: &str, reference: &Self) -> Result<Self, anyhow::Error>;
+}
+
+/// Resolve a relative datetime expression against `reference`. `start_of_day` yields midnight of
+/// the day of the datetime it is given.
+#[cfg(any(feature = "chrono", feature = "time"))]
+fn parse_relative<T, F>(s: &str, reference: &T, start_of_day: F) -> Result<T, anyhow::Error>
+where
+    T: CalendarArithmetic + Clone,
+    F: Fn(&T) -> Option<T>,
+{
+    let out_of_range = || anyhow::anyhow!("relative time {:?} is out of range", s);
+    let expression = s.trim().to_ascii_lowercase();
+
+    let (anchor, sign, duration) = if let Some(duration) = expression.strip_suffix(" ago") {
+        (reference.clone(), -1, Some(duration))
+    } else if let Some(duration) = expression.strip_prefix("in ") {
+        (reference.clone(), 1, Some(duration))
+    } else {
+        let mut rest = expression.as_str();
+        let mut anchor = None;
+
+        for (keyword, days) in [
+            ("now", None),
+            ("today", Some(0)),
+            ("yesterday", Some(-1)),
+            ("tomorrow", Some(1)),
+        ] {
+            if let Some(remainder) = rest.strip_prefix(keyword) {
+                anchor = Some(match days {
+                    None => reference.clone(),
+                    Some(days) => {
+                        let midnight = start_of_day(reference).ok_or_else(out_of_range)?;
+                        let offset =
+                            CalendarDuration::new(0, 0, i64::unsigned_abs(days), Duration::ZERO);
+
+                        if days < 0 {
+                            offset.sub_from(midnight)
+                        } else {
+                            offset.add_to(midnight)
+                        }
+                        .ok_or_else(out_of_range)?
+                    }
+                });
+                rest = remainder.trim_start();
+                break;
+            }
+        }
+
+        let (sign, duration) = if let Some(duration) = rest.strip_prefix('+') {
+            (1, Some(duration))
+        } else if let Some(duration) = rest.strip_prefix('-') {
+            (-1, Some(duration))
+        } else if rest.is_empty() && anchor.is_some() {
+            (1, None)
+        } else {
+            return Err(anyhow::anyhow!(
+                "relative time {:?} must start with an anchor or a sign",
+                s
+            ));
+        };
+
+        (anchor.unwrap_or_else(|| reference.clone()), sign, duration)
+    };
+
+    let duration = match duration {
+        Some(duration) => {
+            if parse_parts(duration)?.is_empty() {
+                return Err(anyhow::anyhow!("relative time {:?} has no duration", s));
+            }
+
+            CalendarDuration::parse(duration)?
+        }
+        None => return Ok(anchor),
+    };
+
+    if sign < 0 {
+        duration.sub_from(anchor)
+    } else {
+        duration.add_to(anchor)
+    }
+    .ok_or_else(out_of_range)
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> ParseRelativeTime for chrono::DateTime<Tz> {
+    fn parse_relative_time(s: &str, reference: &Self) -> Result<Self, anyhow::Error> {
+        parse_relative(s, reference, |dt| {
+            crate::calendar::chrono_impl::resolve_local(
+                dt,
+                &dt.date_naive().and_time(chrono::NaiveTime::MIN),
+            )
+        })
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl ParseRelativeTime for chrono::NaiveDateTime {
+    fn parse_relative_time(s: &str, reference: &Self) -> Result<Self, anyhow::Error> {
+        parse_relative(s, reference, |dt| {
+            Some(dt.date().and_time(chrono::NaiveTime::MIN))
+        })
+    }
+}
+
+#[cfg(feature = "time")]
+impl ParseRelativeTime for time::OffsetDateTime {
+    fn parse_relative_time(s: &str, reference: &Self) -> Result<Self, anyhow::Error> {
+        parse_relative(s, reference, |dt| {
+            Some(dt.replace_time(time::Time::MIDNIGHT))
+        })
+    }
+}
+
+#[cfg(feature = "time")]
+impl ParseRelativeTime for time::PrimitiveDateTime {
+    fn parse_relative_time(s: &str, reference: &Self) -> Result<Self, anyhow::Error> {
+        parse_relative(s, reference, |dt| {
+            Some(dt.replace_time(time::Time::MIDNIGHT))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RelativeTime;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_relative_time() {
+        let now = SystemTime::UNIX_EPOCH + Duration::new(1700000000, 0);
+
+        let relative_table = [
+            (now, 1, false, "just now"),
+            (now - Duration::new(0, 500000000), 1, false, "just now"),
+            (now - Duration::new(330, 0), 1, false, "5m ago"),
+            (now + Duration::new(330, 0), 1, false, "in 5m"),
+            (now - Duration::new(330, 0), 2, false, "5m 30s ago"),
+            (now - Duration::new(330, 0), 1, true, "5 minutes ago"),
+            (now + Duration::new(3600, 0), 1, true, "in 1 hour"),
+            (
+                now - Duration::new(3 * 60 * 60 + 1, 0),
+                2,
+                true,
+                "3 hours ago",
+            ),
+            (
+                now - Duration::new(25 * 60 * 60, 0),
+                2,
+                true,
+                "1 day 1 hour ago",
+            ),
+        ];
+
+        for (time, limit, long, formatted) in relative_table {
+            assert_eq!(
+                RelativeTime::new(time, now)
+                    .truncate(limit)
+                    .long(long)
+                    .to_string(),
+                formatted
+            );
+        }
+
+        assert_eq!(
+            RelativeTime::new(now - Duration::new(30, 0), now)
+                .just_now(Duration::new(60, 0))
+                .to_string(),
+            "just now"
+        );
+        assert_eq!(
+            RelativeTime::new(now - Duration::new(0, 5000000), now)
+                .just_now(Duration::ZERO)
+                .to_string(),
+            "5ms ago"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_chrono_relative_time() {
+        use chrono::{TimeZone, Utc};
+
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let time = Utc.with_ymd_and_hms(2024, 1, 1, 9, 30, 0).unwrap();
+        assert_eq!(RelativeTime::new(time, now).to_string(), "2h ago");
+        assert_eq!(RelativeTime::new(now, time).to_string(), "in 2h");
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn test_time_relative_time() {
+        let now = time::OffsetDateTime::from_unix_timestamp(1700000000).unwrap();
+        let time = now - time::Duration::days(3);
+        assert_eq!(
+            RelativeTime::new(time, now).long(true).to_string(),
+            "3 days ago"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_chrono_parse_relative_time() {
+        use super::ParseRelativeTime;
+        use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+
+        let now = Utc.with_ymd_and_hms(2024, 3, 15, 12, 30, 0).unwrap();
+
+        let relative_table = [
+            ("now", Utc.with_ymd_and_hms(2024, 3, 15, 12, 30, 0)),
+            ("-2h", Utc.with_ymd_and_hms(2024, 3, 15, 10, 30, 0)),
+            ("+30m", Utc.with_ymd_and_hms(2024, 3, 15, 13, 0, 0)),
+            ("now-1d", Utc.with_ymd_and_hms(2024, 3, 14, 12, 30, 0)),
+            ("Now + 1w", Utc.with_ymd_and_hms(2024, 3, 22, 12, 30, 0)),
+            ("now - 1m 0d", Utc.with_ymd_and_hms(2024, 2, 15, 12, 30, 0)),
+            ("24h ago", Utc.with_ymd_and_hms(2024, 3, 14, 12, 30, 0)),
+            ("in 1h 30m", Utc.with_ymd_and_hms(2024, 3, 15, 14, 0, 0)),
+            ("today", Utc.with_ymd_and_hms(2024, 3, 15, 0, 0, 0)),
+            ("yesterday", Utc.with_ymd_and_hms(2024, 3, 14, 0, 0, 0)),
+            ("yesterday + 9h", Utc.with_ymd_and_hms(2024, 3, 14, 9, 0, 0)),
+            ("tomorrow-1s", Utc.with_ymd_and_hms(2024, 3, 15, 23, 59, 59)),
+        ];
+
+        for (expression, expected) in relative_table {
+            assert_eq!(
+                DateTime::parse_relative_time(expression, &now).unwrap(),
+                expected.unwrap(),
+                "{}",
+                expression
+            );
+        }
+
+        for expression in ["", "2h", "now 2h", "later", "now - soon", "- 2x", "ago"] {
+            assert!(
+                DateTime::parse_relative_time(expression, &now).is_err(),
+                "{}",
+                expression
+            );
+        }
+
+        let now = NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_opt(6, 0, 0)
+            .unwrap();
+        assert_eq!(
+            chrono::NaiveDateTime::parse_relative_time("yesterday", &now).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 12, 31)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn test_time_parse_relative_time() {
+        use super::ParseRelativeTime;
+        use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time};
+
+        let date = Date::from_calendar_date(2024, Month::March, 15).unwrap();
+        let now = PrimitiveDateTime::new(date, Time::from_hms(12, 30, 0).unwrap());
+
+        let relative_table = [
+            ("-2h", Time::from_hms(10, 30, 0).unwrap(), 0),
+            ("today", Time::MIDNIGHT, 0),
+            ("tomorrow + 9h", Time::from_hms(9, 0, 0).unwrap(), 1),
+            ("3d ago", Time::from_hms(12, 30, 0).unwrap(), -3),
+        ];
+
+        for (expression, time, days) in relative_table {
+            let expected = PrimitiveDateTime::new(date, time) + time::Duration::days(days);
+
+            assert_eq!(
+                PrimitiveDateTime::parse_relative_time(expression, &now).unwrap(),
+                expected,
+                "{}",
+                expression
+            );
+            assert_eq!(
+                OffsetDateTime::parse_relative_time(expression, &now.assume_utc()).unwrap(),
+                expected.assume_utc(),
+                "{}",
+                expression
+            );
+        }
+    }
+}
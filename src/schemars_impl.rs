@@ -0,0 +1,334 @@
+//! Support for JSON Schemas with `schemars`, so that editors can validate durations in
+//! configuration files.
+
+use crate::{AsTimes, BoundedFancyDuration, FancyDuration, DURATION_PARTS};
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use std::borrow::Cow;
+
+lazy_static::lazy_static! {
+    /// The syntax of a signed duration in a string, see [pattern].
+    static ref PATTERN: String = pattern(true);
+
+    /// The syntax of a duration which cannot be negative in a string, see [pattern].
+    static ref UNSIGNED_PATTERN: String = pattern(false);
+
+    /// The keys of the map form of a duration, see [crate::DurationBreakdown].
+    static ref KEYS: Vec<String> = {
+        let mut keys: Vec<String> = Vec::new();
+
+        for part in &DURATION_PARTS {
+            for key in [part.name().to_string(), format!("{}s", part.name()), part.suffix().to_string()] {
+                if crate::DurationPart::from_key(&key).as_ref() == Some(part) && !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+
+        keys
+    };
+}
+
+/// The syntax of a duration in a string, which is deserialized with [FancyDuration::parse_strict]:
+/// a sign if `signed`, then a bare number of seconds, numbers with a standard unit repeated and
+/// optionally separated by whitespace, or an ISO 8601 duration. A negative zero such as "-0s" is
+/// also accepted by durations which cannot be negative, but is left out of their pattern.
+fn pattern(signed: bool) -> String {
+    let sign = if signed { r"-?\s*" } else { "" };
+    let fancy = r"[0-9]+(?:\.[0-9]+)?|(?:[0-9]+(?:ms|us|ns|[ymwdhs])\s*)+";
+    let date = in_order(&["[0-9]+[Yy]", "[0-9]+[Mm]", "[0-9]+[Ww]", "[0-9]+[Dd]"]);
+    let time = in_order(&["[0-9]+[Hh]", "[0-9]+[Mm]", "[0-9]+(?:[.,][0-9]{1,9})?[Ss]"]);
+
+    format!(r"^\s*{sign}(?:{fancy}|[Pp](?:{date}(?:[Tt]{time})?|[Tt]{time}))\s*$")
+}
+
+/// Whether durations of type `D` may be negative.
+fn is_signed<D: AsTimes>() -> bool {
+    D::parse_to_duration("0")
+        .and_then(|zero| zero.try_from_signed_times(true, 1, 0))
+        .is_ok()
+}
+
+/// A pattern for one or more of `items`, in order.
+fn in_order(items: &[&str]) -> String {
+    let alternatives: Vec<String> = (0..items.len())
+        .map(|first| {
+            items[first + 1..]
+                .iter()
+                .fold(items[first].to_string(), |s, item| {
+                    s + &format!("(?:{})?", item)
+                })
+        })
+        .collect();
+
+    format!("(?:{})", alternatives.join("|"))
+}
+
+/// The schema of a duration, with `description`. Durations are deserialized from numbers of
+/// seconds and maps of parts to counts as well as strings, so the schema is any of these rather
+/// than only a string, which would flag valid configuration as invalid. Negative durations are
+/// only accepted if `signed`.
+fn duration_schema(description: &str, signed: bool) -> Schema {
+    let (pattern, number) = if signed {
+        (&*PATTERN, json_schema!({ "type": "number" }))
+    } else {
+        (
+            &*UNSIGNED_PATTERN,
+            json_schema!({ "type": "number", "minimum": 0 }),
+        )
+    };
+
+    json_schema!({
+        "description": description,
+        "anyOf": [
+            {
+                "type": "string",
+                "pattern": pattern,
+            },
+            number,
+            {
+                "type": "object",
+                "propertyNames": { "enum": *KEYS },
+                "additionalProperties": { "type": "integer", "minimum": 0 },
+            },
+        ],
+        "examples": ["30s", "1h 30m", "2d 12h", "500ms", "PT1H30M", 90, { "hours": 1, "minutes": 30 }],
+    })
+}
+
+/// A duration in fancy duration format such as "1h 30m", ISO 8601 format, a number of seconds, or
+/// a map of parts to counts.
+impl<D> JsonSchema for FancyDuration<D>
+where
+    D: AsTimes + Clone,
+{
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        "FancyDuration".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "fancy_duration::FancyDuration".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        duration_schema(
+            "A duration such as \"1h 30m\", in units of y, m (months, when followed by a smaller \
+             unit), w, d, h, m (minutes), s, ms, us and ns. An ISO 8601 duration such as \
+             \"PT1H30M\", a number of seconds, or a map of units to counts such as \
+             {\"hours\": 1} are also accepted.",
+            is_signed::<D>(),
+        )
+    }
+}
+
+/// A duration as for [FancyDuration] which cannot be negative, with the limits in the description.
+impl<D, const MIN_MS: u64, const MAX_MS: u64> JsonSchema for BoundedFancyDuration<D, MIN_MS, MAX_MS>
+where
+    D: AsTimes + Clone,
+{
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        format!("BoundedFancyDuration_{}_{}", MIN_MS, MAX_MS).into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        format!(
+            "fancy_duration::BoundedFancyDuration<{}, {}>",
+            MIN_MS, MAX_MS
+        )
+        .into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        duration_schema(
+            &format!(
+                "A duration from {} to {}, such as \"1h 30m\".",
+                FancyDuration::new(Self::MIN),
+                FancyDuration::new(Self::MAX)
+            ),
+            false,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KEYS, PATTERN, UNSIGNED_PATTERN};
+    use crate::{BoundedFancyDuration, DurationPart, FancyDuration};
+    use std::time::Duration;
+
+    #[test]
+    fn test_pattern() {
+        let pattern = regex::Regex::new(&PATTERN).unwrap();
+        let unsigned_pattern = regex::Regex::new(&UNSIGNED_PATTERN).unwrap();
+
+        let accepted_table = [
+            ("30s", Duration::new(30, 0)),
+            ("1h 30m", Duration::new(5400, 0)),
+            ("1h30m", Duration::new(5400, 0)),
+            (
+                "1y 2m 3w 4d 5h 6m 7s 8ms 9us 10ns",
+                Duration::new(
+                    360 * 86400 + 2 * 30 * 86400 + 25 * 86400 + 5 * 3600 + 6 * 60 + 7,
+                    8_009_010,
+                ),
+            ),
+            (" 1d 2h ", Duration::new(93600, 0)),
+            ("30", Duration::new(30, 0)),
+            ("1.5", Duration::new(1, 500_000_000)),
+            ("0", Duration::new(0, 0)),
+            ("PT1H", Duration::new(3600, 0)),
+            (
+                "P1Y2M10DT2H30M",
+                Duration::new(360 * 86400 + 70 * 86400 + 9000, 0),
+            ),
+            ("P1W", Duration::new(7 * 86400, 0)),
+            ("PT0.5S", Duration::new(0, 500_000_000)),
+            (" pt1,5s", Duration::new(1, 500_000_000)),
+        ];
+
+        for (s, duration) in accepted_table {
+            assert!(pattern.is_match(s), "{}", s);
+            assert!(unsigned_pattern.is_match(s), "{}", s);
+            assert_eq!(
+                FancyDuration::<Duration>::parse_strict(s)
+                    .unwrap()
+                    .duration(),
+                duration,
+                "{}",
+                s
+            );
+
+            #[cfg(feature = "serde")]
+            assert_eq!(
+                serde_json::from_value::<FancyDuration<Duration>>(serde_json::json!(s))
+                    .unwrap()
+                    .duration(),
+                duration,
+                "{}",
+                s
+            );
+        }
+
+        // negative durations only match the signed pattern.
+        for s in ["-30s", " -1d 2h ", "- 30", "-PT1H", " -pt1,5s"] {
+            assert!(pattern.is_match(s), "{}", s);
+            assert!(!unsigned_pattern.is_match(s), "{}", s);
+            assert!(FancyDuration::<Duration>::parse_strict(s).is_err(), "{}", s);
+
+            #[cfg(feature = "jiff")]
+            assert!(
+                FancyDuration::<jiff::SignedDuration>::parse_strict(s)
+                    .unwrap()
+                    .duration()
+                    .is_negative(),
+                "{}",
+                s
+            );
+        }
+
+        for s in [
+            "",
+            "1x",
+            "h",
+            "1h 30",
+            "1.5h",
+            "1h, 30m",
+            "1h,30m",
+            "1.",
+            "--1s",
+            "1 h",
+            "1hours",
+            "1H",
+            "P",
+            "PT",
+            "P1H",
+            "PT1Y",
+            "P1.5D",
+            "P1D1Y",
+            "PT1.0000000001S",
+        ] {
+            assert!(!pattern.is_match(s), "{}", s);
+            assert!(!unsigned_pattern.is_match(s), "{}", s);
+            assert!(FancyDuration::<Duration>::parse_strict(s).is_err(), "{}", s);
+
+            #[cfg(feature = "serde")]
+            assert!(
+                serde_json::from_value::<FancyDuration<Duration>>(serde_json::json!(s)).is_err(),
+                "{}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_json_schema() {
+        #[derive(schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct Config {
+            timeout: FancyDuration<Duration>,
+            retry: Option<FancyDuration<Duration>>,
+            request_timeout: BoundedFancyDuration<Duration, 100, 300_000>,
+        }
+
+        let schema = serde_json::to_value(schemars::schema_for!(Config)).unwrap();
+        let properties = &schema["properties"];
+        let any_of = &properties["timeout"]["anyOf"];
+
+        assert_eq!(any_of[0]["type"], "string");
+        assert_eq!(any_of[0]["pattern"], *UNSIGNED_PATTERN);
+        assert_eq!(any_of[1]["type"], "number");
+        assert_eq!(any_of[1]["minimum"], 0);
+        assert_eq!(any_of[2]["type"], "object");
+        assert_eq!(properties["timeout"]["examples"][1], "1h 30m");
+        assert_eq!(
+            properties["request_timeout"]["description"],
+            "A duration from 100ms to 5m, such as \"1h 30m\"."
+        );
+        assert_eq!(
+            properties["request_timeout"]["anyOf"][0]["pattern"],
+            *UNSIGNED_PATTERN
+        );
+
+        // each example is accepted.
+        #[cfg(feature = "serde")]
+        for example in properties["timeout"]["examples"].as_array().unwrap() {
+            assert!(
+                serde_json::from_value::<FancyDuration<Duration>>(example.clone()).is_ok(),
+                "{}",
+                example
+            );
+        }
+
+        // each key of the map form is accepted, and "m" is minutes.
+        assert_eq!(any_of[2]["propertyNames"]["enum"], serde_json::json!(*KEYS));
+        assert!(KEYS.iter().all(|key| DurationPart::from_key(key).is_some()));
+        assert!(KEYS.contains(&"m".to_string()));
+        assert!(KEYS.contains(&"hours".to_string()));
+        assert_eq!(KEYS.len(), 10 * 3 - 1);
+    }
+
+    #[test]
+    #[cfg(feature = "jiff")]
+    fn test_json_schema_signed() {
+        let schema =
+            serde_json::to_value(schemars::schema_for!(FancyDuration<jiff::SignedDuration>))
+                .unwrap();
+
+        assert_eq!(schema["anyOf"][0]["pattern"], *PATTERN);
+        assert!(schema["anyOf"][1].get("minimum").is_none());
+        assert_eq!(
+            serde_json::to_value(schemars::schema_for!(
+                BoundedFancyDuration<jiff::SignedDuration, 0, 1000>
+            ))
+            .unwrap()["anyOf"][0]["pattern"],
+            *UNSIGNED_PATTERN
+        );
+    }
+}
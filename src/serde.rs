@@ -0,0 +1,655 @@
+//! Adapters for `#[serde(with = "...")]`, which serialize and deserialize plain duration fields in
+//! the fancy duration format without changing their type to [FancyDuration]:
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use std::time::Duration;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "fancy_duration::serde::std")]
+//!     timeout: Duration,
+//!     #[serde(with = "fancy_duration::serde::std::option")]
+//!     retry: Option<Duration>,
+//!     #[serde(with = "fancy_duration::serde::std::vec")]
+//!     backoff: Vec<Duration>,
+//!     #[serde(with = "fancy_duration::serde::std::map")]
+//!     limits: HashMap<String, Duration>,
+//! }
+//!
+//! let config: Config = serde_json::from_str(
+//!     r#"{"timeout":"30s","retry":null,"backoff":["1s","5s"],"limits":{"read":"1m 30s"}}"#,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(config.timeout, Duration::new(30, 0));
+//! assert_eq!(config.retry, None);
+//! assert_eq!(config.backoff, vec![Duration::new(1, 0), Duration::new(5, 0)]);
+//! assert_eq!(config.limits["read"], Duration::new(90, 0));
+//! ```
+//!
+//! Each module has `option`, `vec` and `map` submodules for `Option`, `Vec` and map values, such
+//! as `HashMap` and `BTreeMap`.
+//!
+//! The modules above serialize in the standard style, e.g. "1h 30m". The same modules under
+//! [compact], [iso8601], [seconds] and [milliseconds] serialize as "1h30m", "PT1H30M", `5400`
+//! and `5400000` respectively. Every style deserializes from all of them, so the style can be
+//! changed without breaking existing data. Numbers, including bare numbers in strings such as
+//! "30", are read as milliseconds by the [milliseconds] modules and as seconds by the others.
+//! Binary formats such as bincode ignore the style, as [FancyDuration] does:
+//!
+//! ```
+//! use std::time::Duration;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "fancy_duration::serde::iso8601::std")]
+//!     timeout: Duration,
+//!     #[serde(with = "fancy_duration::serde::seconds::std::vec")]
+//!     backoff: Vec<Duration>,
+//!     #[serde(with = "fancy_duration::serde::milliseconds::std")]
+//!     poll: Duration,
+//! }
+//!
+//! let config: Config = serde_json::from_str(
+//!     r#"{"timeout":"1h 30m","backoff":["1s","PT5S",10],"poll":250}"#,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(
+//!     serde_json::to_string(&config).unwrap(),
+//!     r#"{"timeout":"PT1H30M","backoff":[1,5,10],"poll":250}"#
+//! );
+//! ```
+
+use crate::{deserialize_in, AsTimes, DurationPart, FancyDuration};
+use ::serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
+use ::std::marker::PhantomData;
+
+/// How durations are written by an adapter module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum WireStyle {
+    /// "1h 30m", as [FancyDuration] serializes.
+    Standard,
+    /// "1h30m", see [FancyDuration::format_compact].
+    Compact,
+    /// "PT1H30M", see [FancyDuration::format_iso8601].
+    Iso8601,
+    /// A number of the unit, e.g. `5400` seconds, which is a float such as `5400.5` when there
+    /// are fractions of the unit.
+    Number(DurationPart),
+}
+
+/// A duration to serialize in a [WireStyle].
+struct Styled<'a, T>(&'a T, &'a WireStyle);
+
+impl<T> Serialize for Styled<'_, T>
+where
+    T: AsTimes + Clone,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let duration = FancyDuration::new(self.0.clone());
+
+        // binary formats have a single representation, see FancyDuration.
+        if !serializer.is_human_readable() {
+            return duration.serialize(serializer);
+        }
+
+        match self.1 {
+            WireStyle::Standard => duration.serialize(serializer),
+            WireStyle::Compact => serializer.serialize_str(&duration.format_compact()),
+            WireStyle::Iso8601 => serializer.serialize_str(&duration.format_iso8601()),
+            WireStyle::Number(unit) => {
+                let (s, ns) = self.0.as_times();
+                let total = s as u128 * 1e9 as u128 + ns as u128;
+                let negative = self.0.is_negative();
+
+                match (
+                    total % unit.as_ns(),
+                    negative,
+                    u64::try_from(total / unit.as_ns()),
+                ) {
+                    (0, false, Ok(value)) => serializer.serialize_u64(value),
+                    (0, true, Ok(value)) if i64::try_from(value).is_ok() => {
+                        serializer.serialize_i64(-(value as i64))
+                    }
+                    _ => {
+                        let value = total as f64 / unit.as_ns() as f64;
+                        serializer.serialize_f64(if negative { -value } else { value })
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The unit numbers are read in by an adapter module.
+trait NumberUnit {
+    const PART: DurationPart;
+}
+
+/// Numbers are seconds.
+enum Seconds {}
+
+impl NumberUnit for Seconds {
+    const PART: DurationPart = DurationPart::Seconds;
+}
+
+/// Numbers are milliseconds.
+enum Milliseconds {}
+
+impl NumberUnit for Milliseconds {
+    const PART: DurationPart = DurationPart::Milliseconds;
+}
+
+/// A duration deserialized with numbers in the unit `U`.
+struct InUnit<T, U>(T, PhantomData<U>);
+
+impl<'de, T, U> Deserialize<'de> for InUnit<T, U>
+where
+    T: AsTimes + Clone,
+    U: NumberUnit,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(InUnit(
+            deserialize_in(deserializer, U::PART)?.0,
+            PhantomData,
+        ))
+    }
+}
+
+fn serialize<T, S>(style: &WireStyle, duration: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: AsTimes + Clone,
+    S: Serializer,
+{
+    Styled(duration, style).serialize(serializer)
+}
+
+fn deserialize<'de, T, U, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: AsTimes + Clone,
+    U: NumberUnit,
+    D: Deserializer<'de>,
+{
+    Ok(InUnit::<T, U>::deserialize(deserializer)?.0)
+}
+
+fn serialize_option<T, S>(
+    style: &WireStyle,
+    duration: &Option<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    T: AsTimes + Clone,
+    S: Serializer,
+{
+    match duration {
+        Some(duration) => serializer.serialize_some(&Styled(duration, style)),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_option<'de, T, U, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: AsTimes + Clone,
+    U: NumberUnit,
+    D: Deserializer<'de>,
+{
+    Ok(Option::<InUnit<T, U>>::deserialize(deserializer)?.map(|duration| duration.0))
+}
+
+fn serialize_vec<T, S>(style: &WireStyle, durations: &[T], serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: AsTimes + Clone,
+    S: Serializer,
+{
+    serializer.collect_seq(durations.iter().map(|duration| Styled(duration, style)))
+}
+
+fn deserialize_vec<'de, T, U, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    T: AsTimes + Clone,
+    U: NumberUnit,
+    D: Deserializer<'de>,
+{
+    Ok(Vec::<InUnit<T, U>>::deserialize(deserializer)?
+        .into_iter()
+        .map(|duration| duration.0)
+        .collect())
+}
+
+fn serialize_map<'a, T, M, K, S>(
+    style: &WireStyle,
+    map: &'a M,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    T: AsTimes + Clone + 'a,
+    &'a M: IntoIterator<Item = (&'a K, &'a T)>,
+    K: Serialize + 'a,
+    S: Serializer,
+{
+    serializer.collect_map(
+        map.into_iter()
+            .map(|(key, duration)| (key, Styled(duration, style))),
+    )
+}
+
+struct MapVisitor<T, U, M, K>(PhantomData<(T, U, M, K)>);
+
+impl<'de, T, U, M, K> Visitor<'de> for MapVisitor<T, U, M, K>
+where
+    T: AsTimes + Clone,
+    U: NumberUnit,
+    M: Default + Extend<(K, T)>,
+    K: Deserialize<'de>,
+{
+    type Value = M;
+
+    fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        formatter.write_str("expecting a map of durations")
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+    where
+        A: ::serde::de::MapAccess<'de>,
+    {
+        let mut map = M::default();
+
+        while let Some((key, duration)) = access.next_entry::<K, InUnit<T, U>>()? {
+            map.extend(Some((key, duration.0)));
+        }
+
+        Ok(map)
+    }
+}
+
+fn deserialize_map<'de, T, U, M, K, D>(deserializer: D) -> Result<M, D::Error>
+where
+    T: AsTimes + Clone,
+    U: NumberUnit,
+    M: Default + Extend<(K, T)>,
+    K: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_map(MapVisitor::<T, U, M, K>(PhantomData))
+}
+
+/// Generate the adapter module for a duration type, with its `option`, `vec` and `map`
+/// submodules, which serialize in `$style`, read numbers in `$unit`, and are described as
+/// serializing `$desc`.
+macro_rules! with_module {
+    ($(#[$attr:meta])* $name:ident, $duration:ty, $style:expr, $unit:ty, $desc:literal) => {
+        $(#[$attr])*
+        pub mod $name {
+            use ::serde::{Deserializer, Serializer};
+
+            #[doc = concat!("Serialize the duration ", $desc, ".")]
+            pub fn serialize<S: Serializer>(
+                duration: &$duration,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                crate::serde::serialize(&$style, duration, serializer)
+            }
+
+            /// Deserialize the duration from any supported style.
+            pub fn deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<$duration, D::Error> {
+                crate::serde::deserialize::<_, $unit, _>(deserializer)
+            }
+
+            /// The adapter for `Option` durations, which are `null` when [None].
+            pub mod option {
+                use ::serde::{Deserializer, Serializer};
+
+                #[doc = concat!("Serialize the optional duration ", $desc, ".")]
+                pub fn serialize<S: Serializer>(
+                    duration: &Option<$duration>,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    crate::serde::serialize_option(
+                        &$style,
+                        duration,
+                        serializer,
+                    )
+                }
+
+                /// Deserialize the optional duration from any supported style.
+                pub fn deserialize<'de, D: Deserializer<'de>>(
+                    deserializer: D,
+                ) -> Result<Option<$duration>, D::Error> {
+                    crate::serde::deserialize_option::<_, $unit, _>(deserializer)
+                }
+            }
+
+            /// The adapter for `Vec` durations.
+            pub mod vec {
+                use ::serde::{Deserializer, Serializer};
+
+                #[doc = concat!("Serialize the durations ", $desc, ".")]
+                pub fn serialize<S: Serializer>(
+                    durations: &[$duration],
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    crate::serde::serialize_vec(
+                        &$style,
+                        durations,
+                        serializer,
+                    )
+                }
+
+                /// Deserialize the durations from any supported style.
+                pub fn deserialize<'de, D: Deserializer<'de>>(
+                    deserializer: D,
+                ) -> Result<Vec<$duration>, D::Error> {
+                    crate::serde::deserialize_vec::<_, $unit, _>(deserializer)
+                }
+            }
+
+            /// The adapter for maps with duration values, such as `HashMap` and `BTreeMap`.
+            pub mod map {
+                use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+                #[doc = concat!("Serialize the map with its values ", $desc, ".")]
+                pub fn serialize<'a, M, K, S>(map: &'a M, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    &'a M: IntoIterator<Item = (&'a K, &'a $duration)>,
+                    K: Serialize + 'a,
+                    S: Serializer,
+                {
+                    crate::serde::serialize_map(&$style, map, serializer)
+                }
+
+                /// Deserialize the map with its values in any supported style.
+                pub fn deserialize<'de, M, K, D>(deserializer: D) -> Result<M, D::Error>
+                where
+                    M: Default + Extend<(K, $duration)>,
+                    K: Deserialize<'de>,
+                    D: Deserializer<'de>,
+                {
+                    crate::serde::deserialize_map::<_, $unit, _, _, _>(deserializer)
+                }
+            }
+        }
+    };
+}
+
+/// Generate the adapter modules of every duration type for a style.
+macro_rules! style_modules {
+    ($style:expr, $unit:ty, $desc:literal) => {
+        with_module!(
+            /// The adapter for [::std::time::Duration].
+            std,
+            ::std::time::Duration,
+            $style,
+            $unit,
+            $desc
+        );
+
+        with_module!(
+            /// The adapter for `time::Duration`.
+            #[cfg(feature = "time")]
+            time,
+            ::time::Duration,
+            $style,
+            $unit,
+            $desc
+        );
+
+        with_module!(
+            /// The adapter for `chrono::Duration`.
+            #[cfg(feature = "chrono")]
+            chrono,
+            ::chrono::Duration,
+            $style,
+            $unit,
+            $desc
+        );
+    };
+}
+
+style_modules!(
+    crate::serde::WireStyle::Standard,
+    crate::serde::Seconds,
+    "in the fancy duration format, e.g. \"1h 30m\""
+);
+
+/// Adapters which serialize in the compact fancy duration format, e.g. "1h30m".
+pub mod compact {
+    style_modules!(
+        crate::serde::WireStyle::Compact,
+        crate::serde::Seconds,
+        "in the compact fancy duration format, e.g. \"1h30m\""
+    );
+}
+
+/// Adapters which serialize in ISO 8601 format, e.g. "PT1H30M".
+pub mod iso8601 {
+    style_modules!(
+        crate::serde::WireStyle::Iso8601,
+        crate::serde::Seconds,
+        "in ISO 8601 format, e.g. \"PT1H30M\""
+    );
+}
+
+/// Adapters which serialize as a number of seconds, e.g. `5400`, which is a float when there are
+/// fractions of a second.
+pub mod seconds {
+    style_modules!(
+        crate::serde::WireStyle::Number(crate::DurationPart::Seconds),
+        crate::serde::Seconds,
+        "as a number of seconds, e.g. `5400`"
+    );
+}
+
+/// Adapters which serialize as a number of milliseconds, e.g. `5400000`, which is a float when
+/// there are fractions of a millisecond. Numbers are also deserialized as milliseconds.
+pub mod milliseconds {
+    style_modules!(
+        crate::serde::WireStyle::Number(crate::DurationPart::Milliseconds),
+        crate::serde::Milliseconds,
+        "as a number of milliseconds, e.g. `5400000`"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use ::serde::{Deserialize, Serialize};
+    use ::std::collections::{BTreeMap, HashMap};
+    use ::std::time::Duration;
+
+    #[test]
+    fn test_with() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Config {
+            #[serde(with = "crate::serde::std")]
+            timeout: Duration,
+            #[serde(with = "crate::serde::std::option")]
+            retry: Option<Duration>,
+            #[serde(with = "crate::serde::std::option", default)]
+            missing: Option<Duration>,
+            #[serde(with = "crate::serde::std::vec")]
+            backoff: Vec<Duration>,
+            #[serde(with = "crate::serde::std::map")]
+            limits: BTreeMap<String, Duration>,
+        }
+
+        let config_table = [
+            (
+                r#"{"timeout":"30s","retry":"1m 30s","missing":null,"backoff":["1s","2s 500ms"],"limits":{"read":"5m","write":"1h"}}"#,
+                Config {
+                    timeout: Duration::new(30, 0),
+                    retry: Some(Duration::new(90, 0)),
+                    missing: None,
+                    backoff: vec![Duration::new(1, 0), Duration::new(2, 500000000)],
+                    limits: BTreeMap::from([
+                        ("read".to_string(), Duration::new(300, 0)),
+                        ("write".to_string(), Duration::new(3600, 0)),
+                    ]),
+                },
+            ),
+            (
+                r#"{"timeout":"0","retry":null,"missing":null,"backoff":[],"limits":{}}"#,
+                Config {
+                    timeout: Duration::ZERO,
+                    retry: None,
+                    missing: None,
+                    backoff: Vec::new(),
+                    limits: BTreeMap::new(),
+                },
+            ),
+        ];
+
+        for (json, config) in config_table {
+            assert_eq!(serde_json::from_str::<Config>(json).unwrap(), config);
+            assert_eq!(serde_json::to_string(&config).unwrap(), json);
+        }
+
+        let config: Config =
+            serde_json::from_str(r#"{"timeout":"1s","retry":null,"backoff":[],"limits":{}}"#)
+                .unwrap();
+        assert_eq!(config.missing, None);
+
+        let config: Config = serde_json::from_str(
+            r#"{"timeout":10,"retry":"PT1M","backoff":[1.5],"limits":{"read":"1m30s"}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.timeout, Duration::new(10, 0));
+        assert_eq!(config.retry, Some(Duration::new(60, 0)));
+        assert_eq!(config.backoff, vec![Duration::new(1, 500000000)]);
+        assert_eq!(config.limits["read"], Duration::new(90, 0));
+
+        for json in [
+            r#"{"timeout":1e30,"retry":null,"backoff":[],"limits":{}}"#,
+            r#"{"timeout":"P1H","retry":null,"backoff":[],"limits":{}}"#,
+            r#"{"timeout":true,"retry":null,"backoff":[],"limits":{}}"#,
+        ] {
+            assert!(serde_json::from_str::<Config>(json).is_err(), "{}", json);
+        }
+
+        #[derive(Serialize, Deserialize)]
+        struct HashConfig {
+            #[serde(with = "crate::serde::std::map")]
+            limits: HashMap<u32, Duration>,
+        }
+
+        let config: HashConfig = serde_json::from_str(r#"{"limits":{"1":"10s"}}"#).unwrap();
+        assert_eq!(config.limits[&1], Duration::new(10, 0));
+        assert_eq!(
+            serde_json::to_string(&config).unwrap(),
+            r#"{"limits":{"1":"10s"}}"#
+        );
+    }
+
+    #[test]
+    fn test_styles() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Config {
+            #[serde(with = "crate::serde::std")]
+            standard: Duration,
+            #[serde(with = "crate::serde::compact::std")]
+            compact: Duration,
+            #[serde(with = "crate::serde::iso8601::std::option")]
+            iso8601: Option<Duration>,
+            #[serde(with = "crate::serde::seconds::std::vec")]
+            seconds: Vec<Duration>,
+            #[serde(with = "crate::serde::seconds::std::map")]
+            limits: BTreeMap<String, Duration>,
+        }
+
+        let config = Config {
+            standard: Duration::new(5400, 0),
+            compact: Duration::new(5400, 0),
+            iso8601: Some(Duration::new(5400, 0)),
+            seconds: vec![Duration::new(5400, 0), Duration::new(5400, 500000000)],
+            limits: BTreeMap::from([("read".to_string(), Duration::ZERO)]),
+        };
+
+        let json = r#"{"standard":"1h 30m","compact":"1h30m","iso8601":"PT1H30M","seconds":[5400,5400.5],"limits":{"read":0}}"#;
+        assert_eq!(serde_json::to_string(&config).unwrap(), json);
+        assert_eq!(serde_json::from_str::<Config>(json).unwrap(), config);
+
+        // every style reads every other style.
+        let json = r#"{"standard":5400,"compact":"PT1H30M","iso8601":"1h 30m","seconds":["1h30m","PT1H30M0.5S"],"limits":{"read":"0"}}"#;
+        assert_eq!(serde_json::from_str::<Config>(json).unwrap(), config);
+
+        let encoded = bincode::serialize(&config).unwrap();
+        assert_eq!(bincode::deserialize::<Config>(&encoded).unwrap(), config);
+    }
+
+    #[test]
+    fn test_milliseconds() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Config {
+            #[serde(with = "crate::serde::milliseconds::std")]
+            poll: Duration,
+            #[serde(with = "crate::serde::milliseconds::std::option")]
+            retry: Option<Duration>,
+            #[serde(with = "crate::serde::milliseconds::std::vec")]
+            backoff: Vec<Duration>,
+            #[serde(with = "crate::serde::milliseconds::std::map")]
+            limits: BTreeMap<String, Duration>,
+        }
+
+        let config = Config {
+            poll: Duration::new(0, 250000000),
+            retry: Some(Duration::new(2, 0)),
+            backoff: vec![Duration::new(0, 1500000)],
+            limits: BTreeMap::from([("read".to_string(), Duration::new(30, 0))]),
+        };
+
+        let json = r#"{"poll":250,"retry":2000,"backoff":[1.5],"limits":{"read":30000}}"#;
+        assert_eq!(serde_json::to_string(&config).unwrap(), json);
+        assert_eq!(serde_json::from_str::<Config>(json).unwrap(), config);
+
+        // bare numbers in strings are milliseconds too, and other formats still apply.
+        let json = r#"{"poll":"250","retry":"2s","backoff":["1.5"],"limits":{"read":"PT30S"}}"#;
+        assert_eq!(serde_json::from_str::<Config>(json).unwrap(), config);
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn test_time_with() {
+        #[derive(Serialize, Deserialize)]
+        struct Config {
+            #[serde(with = "crate::serde::time")]
+            timeout: time::Duration,
+            #[serde(with = "crate::serde::time::vec")]
+            backoff: Vec<time::Duration>,
+        }
+
+        let json = r#"{"timeout":"2m 5s","backoff":["10ms"]}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.timeout, time::Duration::new(125, 0));
+        assert_eq!(config.backoff, vec![time::Duration::milliseconds(10)]);
+        assert_eq!(serde_json::to_string(&config).unwrap(), json);
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_chrono_with() {
+        #[derive(Serialize, Deserialize)]
+        struct Config {
+            #[serde(with = "crate::serde::chrono::option")]
+            timeout: Option<chrono::Duration>,
+            #[serde(with = "crate::serde::chrono::map")]
+            limits: BTreeMap<String, chrono::Duration>,
+        }
+
+        let json = r#"{"timeout":"1h","limits":{"read":"3d"}}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.timeout, Some(chrono::Duration::hours(1)));
+        assert_eq!(config.limits["read"], chrono::Duration::days(3));
+        assert_eq!(serde_json::to_string(&config).unwrap(), json);
+    }
+}
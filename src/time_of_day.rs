@@ -0,0 +1,397 @@
+//! Times of day, such as "9am", "17:30" or "noon".
+
+use crate::{trim_fraction, FancyDuration};
+use std::time::Duration;
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// A [FancyTimeOfDay] is a time of day, kept as the offset from midnight. It parses 12 hour and
+/// 24 hour clock strings, such as "9am", "5:30 pm", "17:30", "17:30:15.5", "noon" and
+/// "midnight", and formats back to either clock. The keywords and the am/pm suffixes are case
+/// insensitive; "12am" is midnight and "12pm" is noon.
+///
+/// Combined with a [FancyDuration], it describes windows of time such as "02:00 + 3h":
+///
+/// ```
+/// use std::time::Duration;
+/// use fancy_duration::{FancyDuration, FancyTimeOfDay};
+///
+/// let start = FancyTimeOfDay::parse("02:00").unwrap();
+/// let length = FancyDuration::<Duration>::parse("3h").unwrap();
+///
+/// assert_eq!(start.offset(), Duration::new(2 * 60 * 60, 0));
+/// assert_eq!(start.wrapping_add(length.duration()).to_string(), "05:00");
+/// assert_eq!(FancyTimeOfDay::parse("5:30 PM").unwrap().to_string(), "17:30");
+/// assert_eq!(FancyTimeOfDay::parse("17:30").unwrap().format_12h(), "5:30pm");
+/// assert_eq!(FancyTimeOfDay::parse("noon").unwrap().format_12h(), "12pm");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FancyTimeOfDay(Duration);
+
+impl FancyTimeOfDay {
+    /// Midnight, the start of the day.
+    pub const MIDNIGHT: Self = Self(Duration::ZERO);
+    /// Noon, the middle of the day.
+    pub const NOON: Self = Self(Duration::from_secs(DAY / 2));
+
+    /// Construct a time of day from its offset from midnight, returning [None] if the offset is
+    /// a day or longer.
+    pub fn new(offset: Duration) -> Option<Self> {
+        if offset.as_secs() < DAY {
+            Some(Self(offset))
+        } else {
+            None
+        }
+    }
+
+    /// Construct a time of day from the hour, minute and second on a 24 hour clock, returning
+    /// [None] if any of them are out of range.
+    pub fn from_hms(hour: u32, minute: u32, second: u32) -> Option<Self> {
+        if hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+
+        Some(Self(Duration::from_secs(
+            hour as u64 * 60 * 60 + minute as u64 * 60 + second as u64,
+        )))
+    }
+
+    /// Parse a time of day on a 12 hour or 24 hour clock. Times on a 24 hour clock require the
+    /// minutes, so that "9" is not mistaken for a duration or an hour on either clock.
+    pub fn parse(s: &str) -> Result<Self, anyhow::Error> {
+        let invalid = || anyhow::anyhow!("invalid time of day {:?}", s);
+        let lowered = s.trim().to_ascii_lowercase();
+
+        match lowered.as_str() {
+            "noon" => return Ok(Self::NOON),
+            "midnight" => return Ok(Self::MIDNIGHT),
+            _ => {}
+        }
+
+        let (clock, pm) = if let Some(clock) = lowered.strip_suffix("am") {
+            (clock.trim_end(), Some(false))
+        } else if let Some(clock) = lowered.strip_suffix("pm") {
+            (clock.trim_end(), Some(true))
+        } else {
+            (lowered.as_str(), None)
+        };
+
+        let mut fields = clock.splitn(3, ':');
+        let hour: u32 = parse_digits(fields.next(), 1..=2).ok_or_else(invalid)?;
+        let minute = fields.next();
+
+        if minute.is_none() && pm.is_none() {
+            return Err(invalid());
+        }
+
+        let minute = match minute {
+            Some(minute) => parse_digits(Some(minute), 2..=2).ok_or_else(invalid)?,
+            None => 0,
+        };
+
+        let (second, nanosecond) = match fields.next() {
+            Some(second) => {
+                let (second, fraction) = match second.split_once('.') {
+                    Some((second, fraction)) => (second, Some(fraction)),
+                    None => (second, None),
+                };
+
+                let nanosecond = match fraction {
+                    Some(fraction) => {
+                        let digits: u32 =
+                            parse_digits(Some(fraction), 1..=9).ok_or_else(invalid)?;
+                        digits * 10u32.pow(9 - fraction.len() as u32)
+                    }
+                    None => 0,
+                };
+
+                (
+                    parse_digits(Some(second), 2..=2).ok_or_else(invalid)?,
+                    nanosecond,
+                )
+            }
+            None => (0, 0),
+        };
+
+        let hour = match pm {
+            Some(pm) => {
+                if !(1..=12).contains(&hour) {
+                    return Err(invalid());
+                }
+
+                hour % 12 + if pm { 12 } else { 0 }
+            }
+            None => hour,
+        };
+
+        let time = Self::from_hms(hour, minute, second).ok_or_else(invalid)?;
+
+        Ok(Self(time.0 + Duration::new(0, nanosecond)))
+    }
+
+    /// The offset from midnight.
+    pub fn offset(&self) -> Duration {
+        self.0
+    }
+
+    /// The hour on a 24 hour clock.
+    pub fn hour(&self) -> u32 {
+        (self.0.as_secs() / (60 * 60)) as u32
+    }
+
+    /// The minute of the hour.
+    pub fn minute(&self) -> u32 {
+        (self.0.as_secs() / 60 % 60) as u32
+    }
+
+    /// The second of the minute.
+    pub fn second(&self) -> u32 {
+        (self.0.as_secs() % 60) as u32
+    }
+
+    /// The nanoseconds past the second.
+    pub fn nanosecond(&self) -> u32 {
+        self.0.subsec_nanos()
+    }
+
+    /// Add a duration, wrapping around midnight; "22:00" plus "3h" is "01:00".
+    pub fn wrapping_add(&self, duration: Duration) -> Self {
+        let ns = (self.0.as_nanos() + duration.as_nanos()) % (DAY as u128 * 1e9 as u128);
+        Self(Duration::new(
+            (ns / 1e9 as u128) as u64,
+            (ns % 1e9 as u128) as u32,
+        ))
+    }
+
+    /// The offset from midnight as a [FancyDuration], e.g. "9h 30m" for "09:30".
+    pub fn fancy_duration(&self) -> FancyDuration<Duration> {
+        FancyDuration::new(self.0)
+    }
+
+    /// Format on a 24 hour clock, e.g. "17:30". Seconds and fractions of a second are only
+    /// included when non-zero.
+    pub fn format(&self) -> String {
+        self.to_string()
+    }
+
+    /// Format on a 12 hour clock, e.g. "5:30pm" or "9am". Minutes, seconds and fractions of a
+    /// second are only included when non-zero.
+    pub fn format_12h(&self) -> String {
+        let mut s = String::new();
+        self.write_12h_to(&mut s)
+            .expect("writing to a String cannot fail");
+        s
+    }
+
+    fn write_12h_to<W: std::fmt::Write>(&self, w: &mut W) -> std::fmt::Result {
+        let hour = match self.hour() % 12 {
+            0 => 12,
+            hour => hour,
+        };
+
+        write!(w, "{}", hour)?;
+
+        if self.minute() != 0 || self.second() != 0 || self.nanosecond() != 0 {
+            write!(w, ":{:02}", self.minute())?;
+            self.write_seconds_to(w)?;
+        }
+
+        w.write_str(if self.hour() < 12 { "am" } else { "pm" })
+    }
+
+    /// Write the seconds and fraction of a second, if there are any.
+    fn write_seconds_to<W: std::fmt::Write>(&self, w: &mut W) -> std::fmt::Result {
+        if self.second() == 0 && self.nanosecond() == 0 {
+            return Ok(());
+        }
+
+        write!(w, ":{:02}", self.second())?;
+
+        if self.nanosecond() != 0 {
+            let (fraction, width) = trim_fraction(self.nanosecond() as u64, 9);
+            write!(w, ".{:0width$}", fraction, width = width)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse a run of ASCII digits whose length is within `len`.
+fn parse_digits<T: std::str::FromStr>(
+    s: Option<&str>,
+    len: std::ops::RangeInclusive<usize>,
+) -> Option<T> {
+    let s = s?;
+
+    if !len.contains(&s.len()) || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    s.parse().ok()
+}
+
+impl std::str::FromStr for FancyTimeOfDay {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl std::fmt::Display for FancyTimeOfDay {
+    /// Formats on a 24 hour clock, see [FancyTimeOfDay::format]. `{:#}` formats on a 12 hour
+    /// clock, see [FancyTimeOfDay::format_12h].
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return self.write_12h_to(f);
+        }
+
+        write!(f, "{:02}:{:02}", self.hour(), self.minute())?;
+        self.write_seconds_to(f)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<FancyTimeOfDay> for chrono::NaiveTime {
+    fn from(value: FancyTimeOfDay) -> Self {
+        chrono::NaiveTime::from_num_seconds_from_midnight_opt(
+            value.0.as_secs() as u32,
+            value.nanosecond(),
+        )
+        .expect("a time of day is always below 24 hours")
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveTime> for FancyTimeOfDay {
+    fn from(value: chrono::NaiveTime) -> Self {
+        use chrono::Timelike;
+
+        // leap seconds are represented by chrono as nanoseconds beyond one second.
+        Self(Duration::new(
+            value.num_seconds_from_midnight() as u64,
+            value.nanosecond().min(999_999_999),
+        ))
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<FancyTimeOfDay> for time::Time {
+    fn from(value: FancyTimeOfDay) -> Self {
+        time::Time::from_hms_nano(
+            value.hour() as u8,
+            value.minute() as u8,
+            value.second() as u8,
+            value.nanosecond(),
+        )
+        .expect("a time of day is always below 24 hours")
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<time::Time> for FancyTimeOfDay {
+    fn from(value: time::Time) -> Self {
+        let (hour, minute, second, nanosecond) = value.as_hms_nano();
+
+        Self(Duration::new(
+            hour as u64 * 60 * 60 + minute as u64 * 60 + second as u64,
+            nanosecond,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FancyTimeOfDay;
+    use std::time::Duration;
+
+    #[test]
+    fn test_time_of_day() {
+        let time_of_day_table = [
+            ("9am", 9 * 3600, 0, "09:00", "9am"),
+            ("9 AM", 9 * 3600, 0, "09:00", "9am"),
+            ("9:30pm", 21 * 3600 + 1800, 0, "21:30", "9:30pm"),
+            ("12am", 0, 0, "00:00", "12am"),
+            ("12:15am", 900, 0, "00:15", "12:15am"),
+            ("12pm", 12 * 3600, 0, "12:00", "12pm"),
+            ("noon", 12 * 3600, 0, "12:00", "12pm"),
+            ("Midnight", 0, 0, "00:00", "12am"),
+            ("17:30", 17 * 3600 + 1800, 0, "17:30", "5:30pm"),
+            ("02:00", 2 * 3600, 0, "02:00", "2am"),
+            ("2:00", 2 * 3600, 0, "02:00", "2am"),
+            ("23:59:59", 86399, 0, "23:59:59", "11:59:59pm"),
+            (
+                "17:30:15.25",
+                17 * 3600 + 1800 + 15,
+                250000000,
+                "17:30:15.25",
+                "5:30:15.25pm",
+            ),
+        ];
+
+        for (s, seconds, nanoseconds, formatted, formatted_12h) in time_of_day_table {
+            let time = FancyTimeOfDay::parse(s).unwrap();
+            assert_eq!(time.offset(), Duration::new(seconds, nanoseconds), "{}", s);
+            assert_eq!(time.format(), formatted, "{}", s);
+            assert_eq!(time.format_12h(), formatted_12h, "{}", s);
+            assert_eq!(format!("{:#}", time), formatted_12h, "{}", s);
+            assert_eq!(FancyTimeOfDay::parse(formatted).unwrap(), time, "{}", s);
+            assert_eq!(FancyTimeOfDay::parse(formatted_12h).unwrap(), time, "{}", s);
+        }
+
+        for s in [
+            "",
+            "9",
+            "24:00",
+            "13pm",
+            "0am",
+            "9:5",
+            "9:60",
+            "9:30:61",
+            "9:30:00.",
+            "nine",
+            "9h",
+            "-1:00",
+            "1:00:00.1234567890",
+        ] {
+            assert!(FancyTimeOfDay::parse(s).is_err(), "{}", s);
+        }
+
+        assert_eq!(FancyTimeOfDay::new(Duration::new(86400, 0)), None);
+        assert_eq!(FancyTimeOfDay::from_hms(24, 0, 0), None);
+        assert_eq!(
+            FancyTimeOfDay::parse("22:00")
+                .unwrap()
+                .wrapping_add(Duration::new(3 * 3600, 0))
+                .to_string(),
+            "01:00"
+        );
+        assert_eq!(
+            FancyTimeOfDay::parse("9:30am")
+                .unwrap()
+                .fancy_duration()
+                .to_string(),
+            "9h 30m"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_chrono_time_of_day() {
+        let time = FancyTimeOfDay::parse("17:30:15.5").unwrap();
+        let naive = chrono::NaiveTime::from_hms_milli_opt(17, 30, 15, 500).unwrap();
+
+        assert_eq!(chrono::NaiveTime::from(time), naive);
+        assert_eq!(FancyTimeOfDay::from(naive), time);
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn test_time_time_of_day() {
+        let time = FancyTimeOfDay::parse("5:30:15.5pm").unwrap();
+        let t = time::Time::from_hms_milli(17, 30, 15, 500).unwrap();
+
+        assert_eq!(time::Time::from(time), t);
+        assert_eq!(FancyTimeOfDay::from(t), time);
+    }
+}
@@ -0,0 +1,267 @@
+//! Pluggable systems of units, for parsing and formatting durations in units other than the
+//! standard years through nanoseconds.
+
+use crate::{Calendar, DURATION_PARTS};
+
+lazy_static::lazy_static! {
+    /// The standard units according to the default [Calendar], in the order of [DURATION_PARTS].
+    pub(crate) static ref STANDARD_UNITS: Vec<Unit> = Calendar::default().units();
+
+    /// A number and a suffix of any length, for unit systems and aliases such as "2sprints".
+    pub(crate) static ref UNIT_FORMAT: regex::Regex = regex::Regex::new(r#"([0-9]+)([a-zA-Z]+)\s*"#).unwrap();
+}
+
+/// A [Unit] is a unit of time in a [UnitSystem], such as an hour, a decade or a sprint.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Unit {
+    name: String,
+    suffixes: Vec<String>,
+    ns: u128,
+}
+
+impl Unit {
+    /// Construct a unit from its singular English name, the suffixes it is parsed from, and its
+    /// length in nanoseconds. The first suffix is used for formatting; without suffixes the name
+    /// is used. Suffixes are letters only, and a length of zero is treated as one nanosecond.
+    pub fn new(name: &str, suffixes: &[&str], ns: u128) -> Self {
+        Self {
+            name: name.to_string(),
+            suffixes: suffixes.iter().map(|suffix| suffix.to_string()).collect(),
+            ns: ns.max(1),
+        }
+    }
+
+    /// The singular English name of the unit, e.g. "hour".
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The suffix used to represent this unit when formatting, e.g. "h" for hours.
+    pub fn suffix(&self) -> &str {
+        self.suffixes.first().unwrap_or(&self.name)
+    }
+
+    /// All suffixes this unit is parsed from.
+    pub fn suffixes(&self) -> &[String] {
+        &self.suffixes
+    }
+
+    /// The length of one of this unit, in nanoseconds.
+    pub fn as_ns(&self) -> u128 {
+        self.ns
+    }
+
+    /// Whether `suffix` represents this unit.
+    pub(crate) fn matches(&self, suffix: &str) -> bool {
+        self.suffixes.iter().any(|s| s == suffix)
+            || (self.suffixes.is_empty() && self.name == suffix)
+    }
+}
+
+/// A [UnitSystem] lists the units durations are parsed from and formatted into, which allows for
+/// game-time units, decades and centuries, or sprints without the standard units. [Calendar] is
+/// the standard system of years through nanoseconds.
+///
+/// Units may share a suffix, which is resolved like the standard "m" for months and minutes:
+/// when parsing, the smallest of the units which is larger than every unit following it is used,
+/// or the largest of them if there is none.
+///
+/// Supply a unit system to [crate::DurationFormat::unit_system] to use it for parsing and
+/// formatting. Only [crate::FancyDuration::parse_with], [crate::FancyDuration::format_with] and
+/// [crate::FancyDuration::write_with] use it: [crate::DurationBreakdown],
+/// [crate::DurationPart], [crate::FancyDuration::format_fixed],
+/// [crate::FancyDuration::format_decimal] and the other formats always use the standard units of
+/// the default [Calendar], with its 360 day year.
+///
+/// ```
+/// use std::time::Duration;
+/// use fancy_duration::{DurationFormat, FancyDuration, Unit};
+///
+/// const YEAR: u128 = 365 * 24 * 60 * 60 * 1_000_000_000;
+///
+/// let units = vec![
+///     Unit::new("century", &["c"], 100 * YEAR),
+///     Unit::new("decade", &["dec"], 10 * YEAR),
+///     Unit::new("year", &["y"], YEAR),
+/// ];
+/// let format = DurationFormat::new().unit_system(&units);
+///
+/// let duration = FancyDuration::<Duration>::parse_with("1c 2dec 3y", &format).unwrap();
+/// assert_eq!(duration.duration().as_secs(), 123 * 365 * 24 * 60 * 60);
+/// assert_eq!(duration.format_with(&format), "1c 2dec 3y");
+/// ```
+pub trait UnitSystem {
+    /// The units of the system, from the largest to the smallest.
+    fn units(&self) -> Vec<Unit>;
+}
+
+impl UnitSystem for Calendar {
+    fn units(&self) -> Vec<Unit> {
+        DURATION_PARTS
+            .iter()
+            .map(|part| Unit::new(part.name(), &[part.suffix()], part.as_ns_in(self)))
+            .collect()
+    }
+}
+
+impl UnitSystem for [Unit] {
+    fn units(&self) -> Vec<Unit> {
+        self.to_vec()
+    }
+}
+
+impl UnitSystem for Vec<Unit> {
+    fn units(&self) -> Vec<Unit> {
+        self.clone()
+    }
+}
+
+/// Parse a string in fancy duration format, tokenized with `format`, into pairs of the index of
+/// the unit in `units` and its value, in reverse order of appearance. `units` must be ordered from
+/// the largest to the smallest. Unknown suffixes are ignored.
+pub(crate) fn parse_units(
+    s: &str,
+    format: &regex::Regex,
+    units: &[Unit],
+) -> Result<Vec<(usize, u64)>, anyhow::Error> {
+    let mut list: Vec<(&str, &str)> = Vec::new();
+
+    for item in format.captures_iter(s) {
+        list.push((item.get(1).unwrap().as_str(), item.get(2).unwrap().as_str()));
+    }
+
+    let mut parts = Vec::with_capacity(list.len());
+    // the index of the largest unit seen so far, which is past the end when none have been.
+    let mut largest = units.len();
+
+    for (value, suffix) in list.iter().rev() {
+        let mut found = None;
+
+        for (index, unit) in units.iter().enumerate() {
+            if unit.matches(suffix) && (found.is_none() || index < largest) {
+                found = Some(index);
+            }
+        }
+
+        let Some(index) = found else {
+            continue;
+        };
+
+        largest = largest.min(index);
+        parts.push((index, value.parse()?));
+    }
+
+    Ok(parts)
+}
+
+/// Write `total` nanoseconds broken down into `units`, which must be ordered from the largest to
+/// the smallest. Time above the largest unit is rolled into it and time below the smallest is
+/// dropped. Units are separated by whitespace if `pad` is set, and no units are written as "0".
+pub(crate) fn write_units<'a, W: std::fmt::Write>(
+    w: &mut W,
+    units: impl Iterator<Item = &'a Unit>,
+    mut total: u128,
+    pad: bool,
+) -> std::fmt::Result {
+    let mut empty = true;
+
+    for unit in units {
+        let value = total / unit.as_ns();
+        total -= value * unit.as_ns();
+
+        if value > 0 {
+            if pad && !empty {
+                w.write_char(' ')?;
+            }
+
+            write!(w, "{}{}", value, unit.suffix())?;
+            empty = false;
+        }
+    }
+
+    if empty {
+        w.write_char('0')?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Unit;
+    use crate::{DurationFormat, DurationPart, FancyDuration};
+    use std::time::Duration;
+
+    #[test]
+    fn test_unit_system() {
+        // a game where an hour passes every real minute, and a season is 30 game days.
+        let units = vec![
+            Unit::new(
+                "season",
+                &["season", "seasons"],
+                30 * 24 * 60 * 1_000_000_000,
+            ),
+            Unit::new("day", &["d"], 24 * 60 * 1_000_000_000),
+            Unit::new("hour", &["h"], 60 * 1_000_000_000),
+            Unit::new("minute", &["m"], 1_000_000_000),
+        ];
+        let format = DurationFormat::new().unit_system(&units);
+
+        let units_table = [
+            ("1season 2d 3h", 30 * 1440 + 2 * 1440 + 180, "1season 2d 3h"),
+            ("2seasons", 2 * 30 * 1440, "2season"),
+            ("90m", 90, "1h 30m"),
+            ("30s", 0, "0"),
+        ];
+
+        for (s, seconds, formatted) in units_table {
+            let duration = FancyDuration::<Duration>::parse_with(s, &format).unwrap();
+            assert_eq!(duration.duration(), Duration::new(seconds, 0), "{}", s);
+            assert_eq!(duration.format_with(&format), formatted, "{}", s);
+        }
+
+        assert_eq!(
+            FancyDuration(Duration::new(1441, 500)).format_with(&format.clone().compact(true)),
+            "1d1m"
+        );
+
+        // the standard parser still reads suffixes of up to two letters.
+        assert_eq!(
+            FancyDuration::<Duration>::parse("10msec")
+                .unwrap()
+                .duration(),
+            Duration::from_millis(10)
+        );
+
+        // other formats keep the standard units.
+        let duration = FancyDuration::<Duration>::parse_with("1season", &format).unwrap();
+        assert_eq!(duration.format(), "12h");
+        assert_eq!(duration.breakdown().hours, 12);
+        assert_eq!(
+            duration.format_fixed(&[DurationPart::Hours, DurationPart::Minutes]),
+            "12h 00m"
+        );
+        assert_eq!(duration.format_decimal(1), "12h");
+
+        // shared suffixes resolve like months and minutes do.
+        let units = vec![
+            Unit::new("sprint", &["s"], 14 * 24 * 60 * 60 * 1_000_000_000),
+            Unit::new("day", &["d"], 24 * 60 * 60 * 1_000_000_000),
+            Unit::new("second", &["s"], 1_000_000_000),
+        ];
+        let format = DurationFormat::new().unit_system(&units);
+
+        assert_eq!(
+            FancyDuration::<Duration>::parse_with("1s 1d", &format)
+                .unwrap()
+                .duration(),
+            Duration::new(15 * 24 * 60 * 60, 0)
+        );
+        assert_eq!(
+            FancyDuration::<Duration>::parse_with("1d 1s", &format)
+                .unwrap()
+                .duration(),
+            Duration::new(24 * 60 * 60 + 1, 0)
+        );
+    }
+}